@@ -0,0 +1,37 @@
+use bevy_vrma::vrm::spring_bone::bench::SpringChainBench;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+const AVATARS: usize = 12;
+const CHAINS_PER_AVATAR: usize = 40;
+const JOINTS_PER_CHAIN: usize = 6;
+
+/// Creates the chains of the scene with [`AVATARS`] avatars.
+fn scene() -> SpringChainBench {
+    SpringChainBench::new(AVATARS * CHAINS_PER_AVATAR, JOINTS_PER_CHAIN)
+}
+
+fn spring_chains(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spring_chains");
+    group.bench_function("sequential", |b| {
+        let mut scene = scene();
+        b.iter(|| scene.advance(black_box(2), 1. / 60., Some(0.5)));
+    });
+    group.bench_function("parallel", |b| {
+        let mut scene = scene();
+        b.iter(|| scene.par_advance(black_box(2), 1. / 60., Some(0.5)));
+    });
+    group.finish();
+
+    // The parallel path must not change the results.
+    let mut sequential = scene();
+    let mut parallel = scene();
+    for _ in 0..60 {
+        sequential.advance(2, 1. / 60., Some(0.5));
+        parallel.par_advance(2, 1. / 60., Some(0.5));
+    }
+    assert_eq!(sequential, parallel);
+}
+
+criterion_group!(benches, spring_chains);
+criterion_main!(benches);
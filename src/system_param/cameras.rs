@@ -1,4 +1,5 @@
 use bevy::ecs::system::SystemParam;
+use bevy::math::primitives::InfinitePlane3d;
 use bevy::math::{Vec2, Vec3};
 use bevy::prelude::{Camera, Entity, GlobalTransform, Query};
 use bevy::render::camera::RenderTarget;
@@ -23,7 +24,7 @@ impl Cameras<'_, '_> {
     pub fn find_camera_from_window(
         &self,
         window_entity: Entity,
-    ) -> Option<CameraQuery> {
+    ) -> Option<CameraQuery<'_>> {
         self
             .cameras
             .iter()
@@ -36,7 +37,7 @@ impl Cameras<'_, '_> {
     pub fn find_camera_from_world_pos(
         &self,
         world_pos: Vec3,
-    ) -> Option<CameraQuery> {
+    ) -> Option<CameraQuery<'_>> {
         self.cameras.iter().find(|(camera, gtf, _)| {
             camera.logical_viewport_rect().is_some_and(|viewport| {
                 let Ok(pos) = camera.world_to_viewport(gtf, world_pos) else {
@@ -47,11 +48,27 @@ impl Cameras<'_, '_> {
         })
     }
 
+    /// Returns `true` if `world_pos` is within the viewport of any camera expanded by `margin` logical pixels.
+    #[inline]
+    pub fn is_in_viewport(
+        &self,
+        world_pos: Vec3,
+        margin: f32,
+    ) -> bool {
+        self.cameras.iter().any(|(camera, gtf, _)| {
+            camera.logical_viewport_rect().is_some_and(|viewport| {
+                camera
+                    .world_to_viewport(gtf, world_pos)
+                    .is_ok_and(|pos| viewport.inflate(margin).contains(pos))
+            })
+        })
+    }
+
     #[inline]
     pub fn find_camera_from_layers(
         &self,
         layers: &RenderLayers,
-    ) -> Option<CameraQuery> {
+    ) -> Option<CameraQuery<'_>> {
         self.cameras
             .iter()
             .find(|(_, _, layer)| layers.intersects(layer))
@@ -79,6 +96,20 @@ impl Cameras<'_, '_> {
             .unwrap();
         Some(pos.extend(0.))
     }
+
+    /// Returns the world position under `viewport_pos` on the plane that faces the camera and passes through `depth_pos`.
+    #[inline]
+    pub fn to_world_pos_at_depth(
+        &self,
+        window_entity: Entity,
+        viewport_pos: Vec2,
+        depth_pos: Vec3,
+    ) -> Option<Vec3> {
+        let (camera, camera_tf, _) = self.find_camera_from_window(window_entity)?;
+        let ray = camera.viewport_to_world(camera_tf, viewport_pos).ok()?;
+        let distance = ray.intersect_plane(depth_pos, InfinitePlane3d::new(camera_tf.forward()))?;
+        Some(ray.get_point(distance))
+    }
 }
 
 #[cfg(test)]
@@ -86,8 +117,13 @@ mod tests {
     use crate::system_param::cameras::Cameras;
     use crate::tests::{test_app, TestResult};
     use bevy::ecs::system::RunSystemOnce;
-    use bevy::prelude::{Camera, Commands, GlobalTransform};
+    use bevy::math::{Vec2, Vec3};
+    use bevy::prelude::{
+        Camera, Commands, Entity, GlobalTransform, Projection, Transform, Window, With,
+    };
+    use bevy::render::camera::RenderTarget;
     use bevy::render::view::RenderLayers;
+    use bevy::window::{PrimaryWindow, WindowRef};
 
     #[test]
     fn test_all_layers() -> TestResult {
@@ -112,4 +148,37 @@ mod tests {
         assert_eq!(layers, RenderLayers::from_layers(&[1, 2]));
         Ok(())
     }
+
+    #[test]
+    fn project_cursor_onto_plane_through_depth_pos() -> TestResult {
+        let mut app = test_app();
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world());
+        app.world_mut().spawn((
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..Default::default()
+            },
+            Projection::default(),
+            GlobalTransform::from(Transform::from_xyz(0., 0., 5.)),
+            RenderLayers::default(),
+        ));
+        app.update();
+
+        let center = app.world().get::<Window>(window).unwrap().size() / 2.;
+        let pos = app.world_mut().run_system_once(move |cameras: Cameras| {
+            cameras.to_world_pos_at_depth(window, center, Vec3::new(0.3, 1., -1.))
+        })?;
+        assert!(pos.unwrap().abs_diff_eq(Vec3::new(0., 0., -1.), 1e-4));
+
+        let pos = app.world_mut().run_system_once(move |cameras: Cameras| {
+            cameras.to_world_pos_at_depth(window, Vec2::ZERO, Vec3::ZERO)
+        })?;
+        let pos = pos.unwrap();
+        assert!(pos.z.abs() < 1e-4);
+        assert!(pos.x < 0. && 0. < pos.y);
+        Ok(())
+    }
 }
@@ -2,8 +2,9 @@ pub mod expressions;
 pub mod extensions;
 pub mod humanoid_bone;
 pub mod loader;
+mod migration;
 mod spawn;
-mod spring_bone;
+pub mod spring_bone;
 
 use crate::new_type;
 use crate::vrm::expressions::VrmExpressionPlugin;
@@ -24,7 +25,7 @@ new_type!(
 );
 
 new_type!(
-    /// The key name of `VRMC_vrm::expressions::preset`.
+    /// The name of a preset or custom expression in `VRMC_vrm::expressions`.
     name: VrmExpression,
     ty: String,
 );
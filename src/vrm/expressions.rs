@@ -1,48 +1,281 @@
-use crate::vrm::extensions::vrmc_vrm::MorphTargetBind;
+mod material;
+
+use crate::system_param::child_searcher::ChildSearcher;
+use crate::vrm::expressions::material::{
+    ExpressionMaterialPlugin, MaterialColorNode, TextureTransformNode,
+};
+use crate::vrm::extensions::vrmc_vrm::{ExpressionOverride, MorphTargetBind, VrmPreset};
 use crate::vrm::extensions::VrmExtensions;
+use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
 use crate::vrm::VrmExpression;
-use bevy::app::Plugin;
+use bevy::app::{Animation, Plugin, PostUpdate, Update};
 use bevy::asset::{Assets, Handle};
 use bevy::core::Name;
 use bevy::gltf::GltfNode;
-use bevy::prelude::{Component, Deref, Reflect};
+use bevy::prelude::*;
+use bevy::render::mesh::inherit_weights;
 use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 
 #[derive(Reflect, Debug, Clone)]
 pub struct ExpressionNode {
     pub name: Name,
     pub morph_target_index: usize,
+
+    /// The weight of the morph target when the expression is fully applied.
+    pub weight: f32,
+}
+
+/// The settings of an expression that affect its weight.
+#[derive(Reflect, Debug, Default, Copy, Clone, PartialEq)]
+pub struct ExpressionSettings {
+    /// If `true`, the weight greater than 0.5 is regarded as 1, otherwise 0.
+    pub is_binary: bool,
+    pub override_blink: ExpressionOverride,
+    pub override_look_at: ExpressionOverride,
+    pub override_mouth: ExpressionOverride,
+}
+
+impl ExpressionSettings {
+    fn new(preset: &VrmPreset) -> Self {
+        Self {
+            is_binary: preset.is_binary,
+            override_blink: preset.override_blink,
+            override_look_at: preset.override_look_at,
+            override_mouth: preset.override_mouth,
+        }
+    }
+
+    /// Applies `isBinary` to the weight.
+    pub fn weight(
+        &self,
+        weight: f32,
+    ) -> f32 {
+        if !self.is_binary {
+            weight
+        } else if 0.5 < weight {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+/// Whether the expression is one of the presets or defined by the author.
+#[derive(Reflect, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ExpressionKind {
+    Preset,
+    Custom,
+}
+
+/// The key of an expression.
+///
+/// The presets and the custom expressions are separate namespaces,
+/// so a custom expression may have the same name as a preset.
+#[derive(Reflect, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ExpressionKey {
+    pub kind: ExpressionKind,
+    pub name: VrmExpression,
+}
+
+impl ExpressionKey {
+    pub fn preset(name: impl Into<VrmExpression>) -> Self {
+        Self {
+            kind: ExpressionKind::Preset,
+            name: name.into(),
+        }
+    }
+
+    pub fn custom(name: impl Into<VrmExpression>) -> Self {
+        Self {
+            kind: ExpressionKind::Custom,
+            name: name.into(),
+        }
+    }
+
+    /// Returns `true` if the expression is a preset in `group`.
+    fn is_preset_in(
+        &self,
+        group: &[&str],
+    ) -> bool {
+        self.kind == ExpressionKind::Preset && group.contains(&self.name.as_str())
+    }
+}
+
+impl std::fmt::Display for ExpressionKey {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        match self.kind {
+            ExpressionKind::Preset => write!(f, "{}", self.name),
+            ExpressionKind::Custom => write!(f, "custom:{}", self.name),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct ExpressionDefinition {
+    pub settings: ExpressionSettings,
+    pub nodes: Vec<ExpressionNode>,
+    pub material_colors: Vec<MaterialColorNode>,
+    pub texture_transforms: Vec<TextureTransformNode>,
 }
 
 #[derive(Component, Deref, Reflect)]
-pub struct VrmExpressionRegistry(HashMap<VrmExpression, Vec<ExpressionNode>>);
+#[require(EvaluatedExpressionWeights)]
+pub struct VrmExpressionRegistry(HashMap<ExpressionKey, ExpressionDefinition>);
 
 impl VrmExpressionRegistry {
     pub fn new(
         extensions: &VrmExtensions,
         node_assets: &Assets<GltfNode>,
         nodes: &[Handle<GltfNode>],
+        materials: &[Handle<StandardMaterial>],
     ) -> Self {
         let Some(expressions) = extensions.vrmc_vrm.expressions.as_ref() else {
             return Self(HashMap::default());
         };
-        Self(
-            expressions
-                .preset
+        let definition = |expression: &VrmPreset| ExpressionDefinition {
+            settings: ExpressionSettings::new(expression),
+            nodes: expression
+                .morph_target_binds
+                .iter()
+                .flatten()
+                .filter_map(|bind| convert_to_node(bind, node_assets, nodes))
+                .collect(),
+            material_colors: expression
+                .material_color_binds
                 .iter()
-                .filter_map(|(preset_name, preset)| {
-                    let binds = preset.morph_target_binds.as_ref()?;
-                    let node = binds
-                        .iter()
-                        .filter_map(|bind| convert_to_node(bind, node_assets, nodes))
-                        .collect::<Vec<_>>();
-                    Some((VrmExpression(preset_name.clone()), node))
-                })
+                .flatten()
+                .filter_map(|bind| MaterialColorNode::new(bind, materials))
                 .collect(),
-        )
+            texture_transforms: expression
+                .texture_transform_binds
+                .iter()
+                .flatten()
+                .filter_map(|bind| TextureTransformNode::new(bind, materials))
+                .collect(),
+        };
+        let presets = expressions
+            .preset
+            .iter()
+            .map(|(name, preset)| (ExpressionKey::preset(name.as_str()), definition(preset)));
+        let customs = expressions
+            .custom
+            .iter()
+            .map(|(name, custom)| (ExpressionKey::custom(name.as_str()), definition(custom)));
+        Self(presets.chain(customs).collect())
+    }
+}
+
+/// The weights of the expressions of the VRM.
+///
+/// This is attached to the VRM entity, and the weights are mixed into [`MorphWeights`]
+/// and the colors of the bound materials every frame.
+/// `isBinary` and the overrides of the expressions are applied while mixing,
+/// and the weights of the morph targets bound to several expressions are summed and clamped to `0..=1`.
+///
+/// While this component is attached, the expressions own the morph targets bound to them,
+/// so the weights written by others such as animation clips are overwritten, and the morph targets of inactive expressions are reset to zero.
+/// Remove this component to drive those morph targets by other means.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct VrmExpressionWeights {
+    /// The weights of the preset expressions.
+    #[serde(default)]
+    pub preset: HashMap<VrmExpression, f32>,
+
+    /// The weights of the custom expressions, which never drive the presets of the same names.
+    #[serde(default)]
+    pub custom: HashMap<VrmExpression, f32>,
+}
+
+impl VrmExpressionWeights {
+    /// Returns the weight of the preset expression, or `0` if it has not been set.
+    pub fn get(
+        &self,
+        expression: &VrmExpression,
+    ) -> f32 {
+        self.preset.get(expression).copied().unwrap_or_default()
+    }
+
+    /// Sets the weight of the preset expression.
+    pub fn set(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+        weight: f32,
+    ) {
+        self.preset.insert(expression.into(), weight);
+    }
+
+    /// Returns the weight of the custom expression, or `0` if it has not been set.
+    pub fn get_custom(
+        &self,
+        expression: &VrmExpression,
+    ) -> f32 {
+        self.custom.get(expression).copied().unwrap_or_default()
+    }
+
+    /// Sets the weight of the custom expression.
+    pub fn set_custom(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+        weight: f32,
+    ) {
+        self.custom.insert(expression.into(), weight);
+    }
+
+    /// Returns the weight of the expression of either kind.
+    pub fn weight(
+        &self,
+        key: &ExpressionKey,
+    ) -> f32 {
+        match key.kind {
+            ExpressionKind::Preset => self.get(&key.name),
+            ExpressionKind::Custom => self.get_custom(&key.name),
+        }
+    }
+
+    /// Sets the weight of the expression of either kind.
+    pub fn set_weight(
+        &mut self,
+        key: &ExpressionKey,
+        weight: f32,
+    ) {
+        match key.kind {
+            ExpressionKind::Preset => self.set(key.name.clone(), weight),
+            ExpressionKind::Custom => self.set_custom(key.name.clone(), weight),
+        }
+    }
+}
+
+/// The weights of the expressions after `isBinary` and the overrides are applied.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct EvaluatedExpressionWeights(HashMap<ExpressionKey, f32>);
+
+impl EvaluatedExpressionWeights {
+    fn get(
+        &self,
+        expression: &ExpressionKey,
+    ) -> f32 {
+        self.0.get(expression).copied().unwrap_or_default()
     }
 }
 
+/// The morph targets of the expressions resolved to the entities with [`MorphWeights`].
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct VrmExpressionMorphs(HashMap<ExpressionKey, Vec<ExpressionMorph>>);
+
+#[derive(Reflect, Debug, Copy, Clone)]
+struct ExpressionMorph {
+    entity: Entity,
+    index: usize,
+    weight: f32,
+}
+
 pub struct VrmExpressionPlugin;
 
 impl Plugin for VrmExpressionPlugin {
@@ -50,7 +283,19 @@ impl Plugin for VrmExpressionPlugin {
         &self,
         app: &mut bevy::app::App,
     ) {
-        app.register_type::<VrmExpressionRegistry>();
+        app.register_type::<VrmExpressionRegistry>()
+            .register_type::<VrmExpressionWeights>()
+            .register_type::<EvaluatedExpressionWeights>()
+            .register_type::<VrmExpressionMorphs>()
+            .add_plugins(ExpressionMaterialPlugin)
+            .add_systems(Update, resolve_expression_morphs)
+            .add_systems(
+                PostUpdate,
+                (evaluate_expressions, mix_expressions)
+                    .chain()
+                    .after(Animation)
+                    .before(inherit_weights),
+            );
     }
 }
 
@@ -64,5 +309,409 @@ fn convert_to_node(
     Some(ExpressionNode {
         name: Name::new(node.name.clone()),
         morph_target_index: bind.index,
+        weight: bind.weight,
     })
 }
+
+fn resolve_expression_morphs(
+    mut commands: Commands,
+    searcher: ChildSearcher,
+    vrms: Query<
+        (Entity, &VrmExpressionRegistry, &HumanoidBoneRegistry),
+        Without<VrmExpressionMorphs>,
+    >,
+) {
+    for (vrm, registry, bone_registry) in vrms.iter() {
+        if !searcher.has_been_spawned_all_bones(vrm, bone_registry) {
+            continue;
+        }
+        let morphs = registry
+            .iter()
+            .map(|(expression, definition)| {
+                let expression_morphs = definition
+                    .nodes
+                    .iter()
+                    .filter_map(|node| {
+                        Some(ExpressionMorph {
+                            entity: searcher.find_from_name(vrm, &node.name)?,
+                            index: node.morph_target_index,
+                            weight: node.weight,
+                        })
+                    })
+                    .collect();
+                (expression.clone(), expression_morphs)
+            })
+            .collect();
+        commands.entity(vrm).insert(VrmExpressionMorphs(morphs));
+    }
+}
+
+fn evaluate_expressions(
+    mut vrms: Query<(
+        &VrmExpressionRegistry,
+        Option<&VrmExpressionWeights>,
+        &mut EvaluatedExpressionWeights,
+    )>
+) {
+    for (registry, weights, mut evaluated) in vrms.iter_mut() {
+        let weight_of = |expression: &ExpressionKey, settings: &ExpressionSettings| {
+            settings.weight(weights.map(|w| w.weight(expression)).unwrap_or_default())
+        };
+        let rates = OverrideRates::new(registry.iter().map(|(expression, definition)| {
+            (
+                expression,
+                &definition.settings,
+                weight_of(expression, &definition.settings),
+            )
+        }));
+        for (expression, definition) in registry.iter() {
+            let weight = rates.apply(expression, weight_of(expression, &definition.settings));
+            evaluated.0.insert(expression.clone(), weight);
+        }
+    }
+}
+
+fn mix_expressions(
+    mut mixed: Local<HashMap<(Entity, usize), f32>>,
+    vrms: Query<(&VrmExpressionMorphs, &EvaluatedExpressionWeights), With<VrmExpressionWeights>>,
+    mut morph_weights: Query<&mut MorphWeights>,
+) {
+    for (morphs, evaluated) in vrms.iter() {
+        mixed.clear();
+        for (expression, expression_morphs) in morphs.0.iter() {
+            let weight = evaluated.get(expression);
+            for morph in expression_morphs.iter() {
+                // The morph targets of inactive expressions are also reset to zero.
+                *mixed.entry((morph.entity, morph.index)).or_default() += morph.weight * weight;
+            }
+        }
+        for ((entity, index), weight) in mixed.iter() {
+            let Ok(mut morph_weights) = morph_weights.get_mut(*entity) else {
+                continue;
+            };
+            let weight = weight.clamp(0., 1.);
+            // Avoid marking the morph weights as changed, which re-uploads them for rendering.
+            if morph_weights
+                .weights()
+                .get(*index)
+                .is_some_and(|current| *current != weight)
+            {
+                morph_weights.weights_mut()[*index] = weight;
+            }
+        }
+    }
+}
+
+const BLINK_EXPRESSIONS: [&str; 3] = ["blink", "blinkLeft", "blinkRight"];
+const LOOK_AT_EXPRESSIONS: [&str; 4] = ["lookUp", "lookDown", "lookLeft", "lookRight"];
+const MOUTH_EXPRESSIONS: [&str; 5] = ["aa", "ih", "ou", "ee", "oh"];
+
+/// The rates at which the blink, look at and mouth expressions are suppressed by other expressions.
+///
+/// If several expressions override the same group, the largest rate is used.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+struct OverrideRates {
+    blink: f32,
+    look_at: f32,
+    mouth: f32,
+}
+
+impl OverrideRates {
+    fn new<'a>(
+        expressions: impl Iterator<Item = (&'a ExpressionKey, &'a ExpressionSettings, f32)>
+    ) -> Self {
+        let mut rates = Self::default();
+        for (expression, settings, weight) in expressions {
+            // The expressions of a group never suppress their own group.
+            if !expression.is_preset_in(&BLINK_EXPRESSIONS) {
+                rates.blink = rates.blink.max(settings.override_blink.rate(weight));
+            }
+            if !expression.is_preset_in(&LOOK_AT_EXPRESSIONS) {
+                rates.look_at = rates.look_at.max(settings.override_look_at.rate(weight));
+            }
+            if !expression.is_preset_in(&MOUTH_EXPRESSIONS) {
+                rates.mouth = rates.mouth.max(settings.override_mouth.rate(weight));
+            }
+        }
+        rates
+    }
+
+    /// Returns the weight of the expression after suppressed by the overrides.
+    ///
+    /// Only the presets belong to the groups, so the custom expressions are never suppressed.
+    fn apply(
+        &self,
+        expression: &ExpressionKey,
+        weight: f32,
+    ) -> f32 {
+        let rate = if expression.is_preset_in(&BLINK_EXPRESSIONS) {
+            self.blink
+        } else if expression.is_preset_in(&LOOK_AT_EXPRESSIONS) {
+            self.look_at
+        } else if expression.is_preset_in(&MOUTH_EXPRESSIONS) {
+            self.mouth
+        } else {
+            0.
+        };
+        weight * (1. - rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::expressions::{
+        evaluate_expressions, mix_expressions, ExpressionDefinition, ExpressionKey,
+        ExpressionMorph, ExpressionSettings, VrmExpressionMorphs, VrmExpressionPlugin,
+        VrmExpressionRegistry, VrmExpressionWeights,
+    };
+    use crate::vrm::extensions::vrmc_vrm::ExpressionOverride;
+    use crate::vrm::extensions::VrmExtensions;
+    use bevy::app::{Animation, App, PostUpdate, Update};
+    use bevy::asset::{AssetApp, Assets};
+    use bevy::ecs::change_detection::DetectChanges;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{
+        Commands, Entity, IntoSystemConfigs, MorphWeights, Query, StandardMaterial,
+    };
+    use bevy::utils::{default, HashMap};
+
+    #[test]
+    fn register_custom_expression_named_after_preset() -> TestResult {
+        let extensions: VrmExtensions = serde_json::from_str(
+            r#"{
+                "VRMC_vrm": {
+                    "specVersion": "1.0",
+                    "humanoid": { "humanBones": {} },
+                    "expressions": {
+                        "preset": { "happy": {} },
+                        "custom": { "happy": { "isBinary": true } }
+                    }
+                }
+            }"#,
+        )?;
+        let registry = VrmExpressionRegistry::new(&extensions, &Assets::default(), &[], &[]);
+        assert_eq!(registry.len(), 2);
+        assert!(!registry[&ExpressionKey::preset("happy")].settings.is_binary);
+        assert!(registry[&ExpressionKey::custom("happy")].settings.is_binary);
+        Ok(())
+    }
+
+    /// Spawns a face with 4 morph targets and a VRM whose expressions are bound to them,
+    /// and returns the entity of the face.
+    ///
+    /// `happy` binds the morph target 0 and 1, `angry` and `sad` bind 0, `aa` binds 2,
+    /// and the custom expression `aa` binds 3.
+    fn spawn_face(
+        app: &mut App,
+        weights: Option<VrmExpressionWeights>,
+        happy: ExpressionSettings,
+    ) -> TestResult<Entity> {
+        let face = app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                let face = commands
+                    .spawn(MorphWeights::new(vec![0.5; 4], None).unwrap())
+                    .id();
+                let morph = |index, weight| ExpressionMorph {
+                    entity: face,
+                    index,
+                    weight,
+                };
+                let expressions = [
+                    (
+                        ExpressionKey::preset("happy"),
+                        happy,
+                        vec![morph(0, 0.8), morph(1, 1.)],
+                    ),
+                    (
+                        ExpressionKey::preset("angry"),
+                        default(),
+                        vec![morph(0, 1.)],
+                    ),
+                    // Out of bounds indices are ignored.
+                    (ExpressionKey::preset("sad"), default(), vec![morph(5, 1.)]),
+                    (ExpressionKey::preset("aa"), default(), vec![morph(2, 1.)]),
+                    (ExpressionKey::custom("aa"), default(), vec![morph(3, 1.)]),
+                ];
+                let mut vrm = commands.spawn((
+                    VrmExpressionRegistry(HashMap::from_iter(expressions.iter().map(
+                        |(key, settings, _)| {
+                            (
+                                key.clone(),
+                                ExpressionDefinition {
+                                    settings: *settings,
+                                    nodes: Vec::new(),
+                                    material_colors: Vec::new(),
+                                    texture_transforms: Vec::new(),
+                                },
+                            )
+                        },
+                    ))),
+                    VrmExpressionMorphs(HashMap::from_iter(
+                        expressions
+                            .into_iter()
+                            .map(|(key, _, morphs)| (key, morphs)),
+                    )),
+                ));
+                if let Some(weights) = weights.clone() {
+                    vrm.insert(weights);
+                }
+                face
+            })?;
+        Ok(face)
+    }
+
+    /// Returns the morph weights of the face spawned by [`spawn_face`] after mixing `weights`.
+    fn mix_face(
+        weights: VrmExpressionWeights,
+        happy: ExpressionSettings,
+    ) -> TestResult<MorphWeights> {
+        let mut app = test_app();
+        let face = spawn_face(&mut app, Some(weights), happy)?;
+        app.world_mut().run_system_once(evaluate_expressions)?;
+        app.world_mut().run_system_once(mix_expressions)?;
+        Ok(app.world().get::<MorphWeights>(face).unwrap().clone())
+    }
+
+    fn weights(expressions: &[(&str, f32)]) -> VrmExpressionWeights {
+        let mut weights = VrmExpressionWeights::default();
+        for (expression, weight) in expressions {
+            weights.set(*expression, *weight);
+        }
+        weights
+    }
+
+    #[test]
+    fn multiply_bind_weight() -> TestResult {
+        let morph_weights = mix_face(weights(&[("happy", 0.5), ("sad", 1.)]), default())?;
+        assert!((morph_weights.weights()[0] - 0.4).abs() < 1e-5);
+        assert!((morph_weights.weights()[1] - 0.5).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn sum_and_clamp_expressions() -> TestResult {
+        let morph_weights = mix_face(weights(&[("happy", 1.), ("angry", 1.)]), default())?;
+        assert_eq!(morph_weights.weights()[0], 1.);
+        Ok(())
+    }
+
+    #[test]
+    fn reset_inactive_expressions() -> TestResult {
+        let morph_weights = mix_face(VrmExpressionWeights::default(), default())?;
+        assert_eq!(morph_weights.weights(), &[0., 0., 0., 0.]);
+        Ok(())
+    }
+
+    #[test]
+    fn leave_morph_weights_of_vrm_without_expression_weights() -> TestResult {
+        let mut app = test_app();
+        let face = spawn_face(&mut app, None, default())?;
+        app.world_mut().run_system_once(evaluate_expressions)?;
+        app.world_mut().run_system_once(mix_expressions)?;
+        let morph_weights = app.world().get::<MorphWeights>(face).unwrap();
+        assert_eq!(morph_weights.weights(), &[0.5; 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn leave_unchanged_morph_weights_unmarked() -> TestResult {
+        let mut app = test_app();
+        app.add_systems(Update, (evaluate_expressions, mix_expressions).chain());
+        let face = spawn_face(&mut app, Some(weights(&[("happy", 0.5)])), default())?;
+        app.update();
+        let changed = |app: &App| {
+            app.world()
+                .entity(face)
+                .get_ref::<MorphWeights>()
+                .unwrap()
+                .last_changed()
+        };
+        let last_changed = changed(&app);
+        app.update();
+        assert_eq!(changed(&app), last_changed);
+        Ok(())
+    }
+
+    #[test]
+    fn overwrite_morph_weights_written_by_animations() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<StandardMaterial>()
+            .add_plugins(VrmExpressionPlugin);
+        // Stands in for `animate_targets` driving the morph weights by a clip.
+        app.add_systems(
+            PostUpdate,
+            (|mut morph_weights: Query<&mut MorphWeights>| {
+                for mut morph_weights in morph_weights.iter_mut() {
+                    morph_weights.weights_mut().fill(0.9);
+                }
+            })
+            .in_set(Animation),
+        );
+        let face = spawn_face(&mut app, Some(weights(&[("happy", 0.5)])), default())?;
+        for _ in 0..3 {
+            app.update();
+            let morph_weights = app.world().get::<MorphWeights>(face).unwrap();
+            assert!((morph_weights.weights()[0] - 0.4).abs() < 1e-5);
+            assert!((morph_weights.weights()[1] - 0.5).abs() < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn snap_binary_expression() -> TestResult {
+        let binary = ExpressionSettings {
+            is_binary: true,
+            ..default()
+        };
+        let morph_weights = mix_face(weights(&[("happy", 0.6)]), binary)?;
+        assert_eq!(morph_weights.weights()[1], 1.);
+        let morph_weights = mix_face(weights(&[("happy", 0.5)]), binary)?;
+        assert_eq!(morph_weights.weights()[1], 0.);
+        Ok(())
+    }
+
+    #[test]
+    fn block_mouth() -> TestResult {
+        let block = ExpressionSettings {
+            override_mouth: ExpressionOverride::Block,
+            ..default()
+        };
+        let morph_weights = mix_face(weights(&[("happy", 0.1), ("aa", 1.)]), block)?;
+        assert_eq!(morph_weights.weights()[2], 0.);
+        let morph_weights = mix_face(weights(&[("happy", 0.), ("aa", 1.)]), block)?;
+        assert_eq!(morph_weights.weights()[2], 1.);
+        Ok(())
+    }
+
+    #[test]
+    fn blend_mouth() -> TestResult {
+        let blend = ExpressionSettings {
+            override_mouth: ExpressionOverride::Blend,
+            ..default()
+        };
+        let morph_weights = mix_face(weights(&[("happy", 0.25), ("aa", 1.)]), blend)?;
+        assert!((morph_weights.weights()[2] - 0.75).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn keep_custom_expressions_separate_from_presets() -> TestResult {
+        let block = ExpressionSettings {
+            override_mouth: ExpressionOverride::Block,
+            ..default()
+        };
+        let mut custom = weights(&[("happy", 1.)]);
+        custom.set_custom("aa", 1.);
+        let morph_weights = mix_face(custom, block)?;
+        // The custom expression is not in the mouth group, so it is not blocked.
+        assert_eq!(morph_weights.weights()[2], 0.);
+        assert_eq!(morph_weights.weights()[3], 1.);
+
+        let morph_weights = mix_face(weights(&[("aa", 1.)]), default())?;
+        assert_eq!(morph_weights.weights()[2], 1.);
+        assert_eq!(morph_weights.weights()[3], 0.);
+        Ok(())
+    }
+}
@@ -0,0 +1,499 @@
+//! Drives the materials bound to the expressions by `materialColorBinds` and `textureTransformBinds`.
+//!
+//! The bound materials are cloned for each VRM, so VRMs spawned from the same asset do not share the colors.
+//! The copies of the bound materials that the glTF loader creates for meshes under negatively scaled nodes are cloned together.
+//! Only `color` and `emissionColor` have counterparts in [`StandardMaterial`];
+//! the other color types are parsed but ignored.
+
+use crate::system_param::child_searcher::ChildSearcher;
+use crate::vrm::expressions::{
+    evaluate_expressions, EvaluatedExpressionWeights, ExpressionKey, VrmExpressionRegistry,
+};
+use crate::vrm::extensions::vrmc_vrm::{
+    MaterialColorBind, MaterialColorType, TextureTransformBind,
+};
+use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
+use bevy::app::{App, Plugin, PostUpdate, Update};
+use bevy::asset::{AssetId, Assets, Handle};
+use bevy::color::ColorToComponents;
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub struct ExpressionMaterialPlugin;
+
+impl Plugin for ExpressionMaterialPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<VrmExpressionMaterials>()
+            .add_systems(Update, instance_expression_materials)
+            .add_systems(
+                PostUpdate,
+                mix_expression_materials.after(evaluate_expressions),
+            );
+    }
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct MaterialColorNode {
+    /// The material of the VRM asset, which is replaced with the instance of each VRM.
+    pub material: Handle<StandardMaterial>,
+    pub ty: MaterialColorType,
+    pub target: LinearRgba,
+}
+
+impl MaterialColorNode {
+    pub(super) fn new(
+        bind: &MaterialColorBind,
+        materials: &[Handle<StandardMaterial>],
+    ) -> Option<Self> {
+        Some(Self {
+            material: materials.get(bind.material)?.clone(),
+            ty: bind.ty,
+            target: LinearRgba::from_f32_array(bind.target_value),
+        })
+    }
+}
+
+#[derive(Reflect, Debug, Clone)]
+pub struct TextureTransformNode {
+    /// The material of the VRM asset, which is replaced with the instance of each VRM.
+    pub material: Handle<StandardMaterial>,
+    pub scale: Vec2,
+    pub offset: Vec2,
+}
+
+impl TextureTransformNode {
+    pub(super) fn new(
+        bind: &TextureTransformBind,
+        materials: &[Handle<StandardMaterial>],
+    ) -> Option<Self> {
+        Some(Self {
+            material: materials.get(bind.material)?.clone(),
+            scale: Vec2::from_array(bind.scale),
+            offset: Vec2::from_array(bind.offset),
+        })
+    }
+}
+
+/// The materials bound to the expressions, cloned for the VRM.
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct VrmExpressionMaterials {
+    materials: Vec<InstancedMaterial>,
+    colors: Vec<ExpressionMaterialColor>,
+    texture_transforms: Vec<ExpressionTextureTransform>,
+}
+
+#[derive(Reflect, Debug, Clone)]
+struct InstancedMaterial {
+    handle: Handle<StandardMaterial>,
+    /// The clone of the inverted copy of the material used by meshes under negatively scaled nodes.
+    inverted: Option<Handle<StandardMaterial>>,
+    base_color: LinearRgba,
+    emissive: LinearRgba,
+    uv_transform: Affine2,
+}
+
+#[derive(Reflect, Debug, Clone)]
+struct ExpressionMaterialColor {
+    expression: ExpressionKey,
+    /// The index of [`VrmExpressionMaterials::materials`].
+    material: usize,
+    ty: MaterialColorType,
+    target: LinearRgba,
+}
+
+#[derive(Reflect, Debug, Clone)]
+struct ExpressionTextureTransform {
+    expression: ExpressionKey,
+    /// The index of [`VrmExpressionMaterials::materials`].
+    material: usize,
+    scale: Vec2,
+    offset: Vec2,
+}
+
+fn instance_expression_materials(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    searcher: ChildSearcher,
+    vrms: Query<
+        (Entity, &VrmExpressionRegistry, &HumanoidBoneRegistry),
+        Without<VrmExpressionMaterials>,
+    >,
+    children: Query<&Children>,
+    mut mesh_materials: Query<&mut MeshMaterial3d<StandardMaterial>>,
+) {
+    for (vrm, registry, bone_registry) in vrms.iter() {
+        if !searcher.has_been_spawned_all_bones(vrm, bone_registry) {
+            continue;
+        }
+        let mut instances = Vec::new();
+        let mut indices = HashMap::<AssetId<StandardMaterial>, usize>::default();
+        let mut inverted_indices = HashMap::<AssetId<StandardMaterial>, usize>::default();
+        let bound_materials = registry.values().flat_map(|definition| {
+            definition
+                .material_colors
+                .iter()
+                .map(|node| &node.material)
+                .chain(
+                    definition
+                        .texture_transforms
+                        .iter()
+                        .map(|node| &node.material),
+                )
+        });
+        for handle in bound_materials {
+            if indices.contains_key(&handle.id()) {
+                continue;
+            }
+            let Some(material) = materials.get(handle).cloned() else {
+                continue;
+            };
+            let inverted = inverted_material(handle, &asset_server).and_then(|inverted| {
+                let inverted_material = materials.get(&inverted).cloned()?;
+                inverted_indices.insert(inverted.id(), instances.len());
+                Some(materials.add(inverted_material))
+            });
+            indices.insert(handle.id(), instances.len());
+            instances.push(InstancedMaterial {
+                base_color: material.base_color.to_linear(),
+                emissive: material.emissive,
+                uv_transform: material.uv_transform,
+                handle: materials.add(material),
+                inverted,
+            });
+        }
+        for entity in children.iter_descendants(vrm) {
+            let Ok(mut mesh_material) = mesh_materials.get_mut(entity) else {
+                continue;
+            };
+            let id = mesh_material.0.id();
+            if let Some(index) = indices.get(&id) {
+                mesh_material.0 = instances[*index].handle.clone();
+            } else if let Some(inverted) = inverted_indices
+                .get(&id)
+                .and_then(|index| instances[*index].inverted.clone())
+            {
+                mesh_material.0 = inverted;
+            }
+        }
+        let colors = registry
+            .iter()
+            .flat_map(|(expression, definition)| {
+                definition.material_colors.iter().filter_map(|node| {
+                    Some(ExpressionMaterialColor {
+                        expression: expression.clone(),
+                        material: *indices.get(&node.material.id())?,
+                        ty: node.ty,
+                        target: node.target,
+                    })
+                })
+            })
+            .collect();
+        let texture_transforms = registry
+            .iter()
+            .flat_map(|(expression, definition)| {
+                definition.texture_transforms.iter().filter_map(|node| {
+                    Some(ExpressionTextureTransform {
+                        expression: expression.clone(),
+                        material: *indices.get(&node.material.id())?,
+                        scale: node.scale,
+                        offset: node.offset,
+                    })
+                })
+            })
+            .collect();
+        commands.entity(vrm).insert(VrmExpressionMaterials {
+            materials: instances,
+            colors,
+            texture_transforms,
+        });
+    }
+}
+
+/// Returns the copy of the material that the glTF loader creates for meshes under negatively scaled nodes,
+/// labeled as [`GltfAssetLabel::Material`](bevy::gltf::GltfAssetLabel::Material) with `is_scale_inverted`.
+fn inverted_material(
+    handle: &Handle<StandardMaterial>,
+    asset_server: &AssetServer,
+) -> Option<Handle<StandardMaterial>> {
+    let path = handle.path()?;
+    let label = format!("{} (inverted)", path.label()?);
+    asset_server.get_handle(path.clone().with_label(label))
+}
+
+fn mix_expression_materials(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    vrms: Query<(&VrmExpressionMaterials, &EvaluatedExpressionWeights)>,
+) {
+    for (bound, evaluated) in vrms.iter() {
+        for (index, instance) in bound.materials.iter().enumerate() {
+            let mut base_color = instance.base_color;
+            let mut emissive = instance.emissive;
+            for color in bound.colors.iter().filter(|color| color.material == index) {
+                let weight = evaluated.get(&color.expression);
+                match color.ty {
+                    MaterialColorType::Color => {
+                        base_color += (color.target - instance.base_color) * weight;
+                    }
+                    MaterialColorType::EmissionColor => {
+                        emissive += (color.target - instance.emissive) * weight;
+                    }
+                    _ => {}
+                }
+            }
+            // The scales and offsets of the active expressions are added to the identity.
+            let mut scale = Vec2::ONE;
+            let mut offset = Vec2::ZERO;
+            for transform in bound
+                .texture_transforms
+                .iter()
+                .filter(|transform| transform.material == index)
+            {
+                let weight = evaluated.get(&transform.expression);
+                scale += (transform.scale - Vec2::ONE) * weight;
+                offset += transform.offset * weight;
+            }
+            let uv_transform =
+                Affine2::from_scale_angle_translation(scale, 0., offset) * instance.uv_transform;
+
+            let base_color = Color::LinearRgba(base_color);
+            for handle in std::iter::once(&instance.handle).chain(instance.inverted.as_ref()) {
+                // Avoid marking the material as changed, which re-prepares it for rendering.
+                let Some(material) = materials.get(handle) else {
+                    continue;
+                };
+                if material.base_color == base_color
+                    && material.emissive == emissive
+                    && material.uv_transform == uv_transform
+                {
+                    continue;
+                }
+                if let Some(material) = materials.get_mut(handle) {
+                    material.base_color = base_color;
+                    material.emissive = emissive;
+                    material.uv_transform = uv_transform;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::expressions::material::{
+        instance_expression_materials, mix_expression_materials, ExpressionMaterialColor,
+        ExpressionTextureTransform, InstancedMaterial, MaterialColorNode, VrmExpressionMaterials,
+    };
+    use crate::vrm::expressions::{
+        EvaluatedExpressionWeights, ExpressionDefinition, ExpressionKey, VrmExpressionRegistry,
+    };
+    use crate::vrm::extensions::vrmc_vrm::MaterialColorType;
+    use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
+    use bevy::app::App;
+    use bevy::asset::{AssetApp, Assets, Handle};
+    use bevy::color::{Color, LinearRgba};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::{Affine2, Vec2};
+    use bevy::prelude::{BuildChildren, Commands, Entity, MeshMaterial3d, StandardMaterial};
+    use bevy::utils::{default, HashMap};
+
+    fn add_material(
+        app: &mut App,
+        material: StandardMaterial,
+    ) -> Handle<StandardMaterial> {
+        app.world_mut()
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(material)
+    }
+
+    /// Spawns a VRM whose `blush` expression is bound to `material`, and returns the VRM and its mesh.
+    fn spawn_vrm(
+        app: &mut App,
+        material: Handle<StandardMaterial>,
+    ) -> TestResult<(Entity, Entity)> {
+        let entities = app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                let definition = ExpressionDefinition {
+                    settings: default(),
+                    nodes: Vec::new(),
+                    material_colors: vec![MaterialColorNode {
+                        material: material.clone(),
+                        ty: MaterialColorType::Color,
+                        target: LinearRgba::RED,
+                    }],
+                    texture_transforms: Vec::new(),
+                };
+                let vrm = commands
+                    .spawn((
+                        VrmExpressionRegistry(HashMap::from_iter([(
+                            ExpressionKey::preset("blush"),
+                            definition,
+                        )])),
+                        HumanoidBoneRegistry::default(),
+                    ))
+                    .id();
+                let mesh = commands
+                    .spawn(MeshMaterial3d(material.clone()))
+                    .set_parent(vrm)
+                    .id();
+                (vrm, mesh)
+            })?;
+        Ok(entities)
+    }
+
+    #[test]
+    fn clone_materials_per_vrm() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<StandardMaterial>();
+        let material = add_material(&mut app, StandardMaterial::default());
+        let (_, mesh1) = spawn_vrm(&mut app, material.clone())?;
+        let (_, mesh2) = spawn_vrm(&mut app, material.clone())?;
+        app.world_mut()
+            .run_system_once(instance_expression_materials)?;
+
+        let instance1 = &app
+            .world()
+            .get::<MeshMaterial3d<StandardMaterial>>(mesh1)
+            .unwrap()
+            .0;
+        let instance2 = &app
+            .world()
+            .get::<MeshMaterial3d<StandardMaterial>>(mesh2)
+            .unwrap()
+            .0;
+        assert_ne!(instance1, &material);
+        assert_ne!(instance1, instance2);
+        Ok(())
+    }
+
+    #[test]
+    fn mix_color_and_emission() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<StandardMaterial>();
+        let handle = add_material(&mut app, StandardMaterial::default());
+        let colors = [
+            (MaterialColorType::Color, LinearRgba::BLACK),
+            (MaterialColorType::EmissionColor, LinearRgba::RED),
+            // MToon properties are not supported.
+            (MaterialColorType::RimColor, LinearRgba::BLUE),
+        ];
+        app.world_mut().spawn((
+            VrmExpressionMaterials {
+                materials: vec![InstancedMaterial {
+                    handle: handle.clone(),
+                    base_color: LinearRgba::WHITE,
+                    emissive: LinearRgba::BLACK,
+                    uv_transform: Affine2::IDENTITY,
+                    inverted: None,
+                }],
+                colors: colors
+                    .into_iter()
+                    .map(|(ty, target)| ExpressionMaterialColor {
+                        expression: ExpressionKey::preset("blush"),
+                        material: 0,
+                        ty,
+                        target,
+                    })
+                    .collect(),
+                texture_transforms: Vec::new(),
+            },
+            EvaluatedExpressionWeights(HashMap::from_iter([(ExpressionKey::preset("blush"), 0.5)])),
+        ));
+        app.world_mut().run_system_once(mix_expression_materials)?;
+
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        let material = materials.get(&handle).unwrap();
+        assert_eq!(
+            material.base_color,
+            Color::LinearRgba(LinearRgba::new(0.5, 0.5, 0.5, 1.))
+        );
+        assert_eq!(material.emissive, LinearRgba::new(0.5, 0., 0., 1.));
+        Ok(())
+    }
+
+    #[test]
+    fn mix_inverted_copy_together() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<StandardMaterial>();
+        let handle = add_material(&mut app, StandardMaterial::default());
+        let inverted = add_material(&mut app, StandardMaterial::default());
+        app.world_mut().spawn((
+            VrmExpressionMaterials {
+                materials: vec![InstancedMaterial {
+                    handle: handle.clone(),
+                    base_color: LinearRgba::WHITE,
+                    emissive: LinearRgba::BLACK,
+                    uv_transform: Affine2::IDENTITY,
+                    inverted: Some(inverted.clone()),
+                }],
+                colors: vec![ExpressionMaterialColor {
+                    expression: ExpressionKey::preset("blush"),
+                    material: 0,
+                    ty: MaterialColorType::Color,
+                    target: LinearRgba::RED,
+                }],
+                texture_transforms: Vec::new(),
+            },
+            EvaluatedExpressionWeights(HashMap::from_iter([(ExpressionKey::preset("blush"), 1.)])),
+        ));
+        app.world_mut().run_system_once(mix_expression_materials)?;
+
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        for handle in [&handle, &inverted] {
+            assert_eq!(
+                materials.get(handle).unwrap().base_color,
+                Color::LinearRgba(LinearRgba::RED)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn combine_texture_transforms() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<StandardMaterial>();
+        let handle = add_material(&mut app, StandardMaterial::default());
+        let transform = |expression: &str, scale, offset| ExpressionTextureTransform {
+            expression: ExpressionKey::preset(expression),
+            material: 0,
+            scale,
+            offset,
+        };
+        app.world_mut().spawn((
+            VrmExpressionMaterials {
+                materials: vec![InstancedMaterial {
+                    handle: handle.clone(),
+                    base_color: LinearRgba::WHITE,
+                    emissive: LinearRgba::BLACK,
+                    uv_transform: Affine2::IDENTITY,
+                    inverted: None,
+                }],
+                colors: Vec::new(),
+                texture_transforms: vec![
+                    transform("eyeA", Vec2::ONE, Vec2::new(0.5, 0.)),
+                    transform("eyeB", Vec2::new(2., 1.), Vec2::new(0., 0.5)),
+                ],
+            },
+            EvaluatedExpressionWeights(HashMap::from_iter([
+                (ExpressionKey::preset("eyeA"), 1.),
+                (ExpressionKey::preset("eyeB"), 0.5),
+            ])),
+        ));
+        app.world_mut().run_system_once(mix_expression_materials)?;
+
+        let materials = app.world().resource::<Assets<StandardMaterial>>();
+        let uv_transform = materials.get(&handle).unwrap().uv_transform;
+        assert_eq!(
+            uv_transform,
+            Affine2::from_scale_angle_translation(Vec2::new(1.5, 1.), 0., Vec2::new(0.5, 0.25))
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+pub mod vrm0;
 pub mod vrmc_spring_bone;
 pub mod vrmc_vrm;
 
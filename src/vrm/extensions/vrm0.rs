@@ -0,0 +1,678 @@
+//! The legacy `VRM` extension used by VRM 0.x.
+//!
+//! VRM 0.x files are converted into [`VrmcVrm`] while loading, so the rest of the crate only handles VRM 1.0 semantics.
+//! See [`VRM 0.x specification`](https://github.com/vrm-c/vrm-specification/tree/master/specification/0.0).
+
+use crate::vrm::extensions::vrmc_spring_bone::{
+    Collider, ColliderGroup, ColliderShape, Sphere, Spring, SpringJoint, VRMCSpringBone,
+};
+use crate::vrm::extensions::vrmc_vrm::{
+    ExpressionOverride, Expressions, Humanoid, MaterialColorBind, MaterialColorType, Meta,
+    MorphTargetBind, TextureTransformBind, VrmPreset, VrmcVrm,
+};
+use crate::vrm::extensions::VrmNode;
+use bevy::color::{ColorToComponents, LinearRgba, Srgba};
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Vrm0 {
+    #[serde(rename = "specVersion")]
+    pub spec_version: Option<String>,
+    pub meta: Option<Vrm0Meta>,
+    pub humanoid: Vrm0Humanoid,
+    #[serde(rename = "blendShapeMaster")]
+    pub blend_shape_master: Option<BlendShapeMaster>,
+    #[serde(rename = "secondaryAnimation")]
+    pub secondary_animation: Option<SecondaryAnimation>,
+}
+
+impl Vrm0 {
+    /// Converts into `VRMC_vrm`.
+    ///
+    /// `mesh_nodes` is the list of the mesh index referenced by each glTF node,
+    /// which is needed because VRM 0.x binds blend shapes to meshes instead of nodes.
+    /// Likewise, `material_names` is the name of each glTF material, which `materialValues` refer to.
+    pub fn to_vrmc_vrm(
+        &self,
+        mesh_nodes: &[Option<usize>],
+        material_names: &[Option<String>],
+    ) -> VrmcVrm {
+        VrmcVrm {
+            expressions: self
+                .blend_shape_master
+                .as_ref()
+                .map(|master| master.to_expressions(mesh_nodes, material_names)),
+            humanoid: self.humanoid.to_humanoid(),
+            meta: self.meta.as_ref().map(Vrm0Meta::to_meta),
+            spec_version: "1.0".to_string(),
+        }
+    }
+
+    /// Converts `secondaryAnimation` into `VRMC_springBone`.
+    ///
+    /// `node_children` is the list of the children indices of each glTF node,
+    /// which is needed to expand each root bone of the bone groups into the joint chains.
+    pub fn to_vrmc_spring_bone(
+        &self,
+        node_children: &[Vec<usize>],
+    ) -> Option<VRMCSpringBone> {
+        self.secondary_animation
+            .as_ref()
+            .map(|secondary| secondary.to_spring_bone(node_children))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Vrm0Meta {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    #[serde(rename = "allowedUserName")]
+    pub allowed_user_name: Option<String>,
+    #[serde(rename = "violentUssageName")]
+    pub violent_usage_name: Option<String>,
+    #[serde(rename = "sexualUssageName")]
+    pub sexual_usage_name: Option<String>,
+    #[serde(rename = "commercialUssageName")]
+    pub commercial_usage_name: Option<String>,
+    #[serde(rename = "licenseName")]
+    pub license_name: Option<String>,
+    #[serde(rename = "otherLicenseUrl")]
+    pub other_license_url: Option<String>,
+}
+
+impl Vrm0Meta {
+    fn to_meta(&self) -> Meta {
+        let allowed = |value: &Option<String>| value.as_deref() == Some("Allow");
+        Meta {
+            allow_antisocial_or_hate_usage: false,
+            allow_excessively_sexual_usage: allowed(&self.sexual_usage_name),
+            allow_excessively_violent_usage: allowed(&self.violent_usage_name),
+            allow_political_or_religious_usage: false,
+            allow_redistribution: self.license_name.as_deref().is_some_and(|license| {
+                license != "Redistribution_Prohibited" && license != "Other"
+            }),
+            authors: self.author.iter().cloned().collect(),
+            avatar_permission: self.allowed_user_name.as_deref().map(|name| {
+                match name {
+                    "Everyone" => "everyone",
+                    "ExplicitlyLicensedPerson" => "onlySeparatelyLicensedPerson",
+                    _ => "onlyAuthor",
+                }
+                .to_string()
+            }),
+            commercial_usage: self.commercial_usage_name.as_deref().map(|name| {
+                if name == "Allow" {
+                    "personalProfit"
+                } else {
+                    "personalNonProfit"
+                }
+                .to_string()
+            }),
+            credit_notation: None,
+            license_url: None,
+            modification: None,
+            name: self.title.clone(),
+            other_license_url: self.other_license_url.clone(),
+            thumbnail_image: None,
+            version: self.version.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Vrm0Humanoid {
+    #[serde(rename = "humanBones")]
+    pub human_bones: Vec<Vrm0HumanBone>,
+}
+
+impl Vrm0Humanoid {
+    fn to_humanoid(&self) -> Humanoid {
+        Humanoid {
+            human_bones: self
+                .human_bones
+                .iter()
+                .map(|bone| {
+                    (
+                        convert_bone_name(&bone.bone).to_string(),
+                        VrmNode { node: bone.node },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Vrm0HumanBone {
+    pub bone: String,
+    pub node: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct BlendShapeMaster {
+    #[serde(rename = "blendShapeGroups")]
+    pub blend_shape_groups: Vec<BlendShapeGroup>,
+}
+
+impl BlendShapeMaster {
+    fn to_expressions(
+        &self,
+        mesh_nodes: &[Option<usize>],
+        material_names: &[Option<String>],
+    ) -> Expressions {
+        let mut expressions = Expressions {
+            preset: HashMap::default(),
+            custom: HashMap::default(),
+        };
+        for group in self.blend_shape_groups.iter() {
+            // The groups with unknown presets are custom expressions addressed by their names.
+            match group.preset_name.as_deref().and_then(convert_preset_name) {
+                Some(preset_name) => {
+                    expressions.preset.insert(
+                        preset_name.to_string(),
+                        group.to_preset(mesh_nodes, material_names),
+                    );
+                }
+                None if !group.name.is_empty() => {
+                    expressions.custom.insert(
+                        group.name.clone(),
+                        group.to_preset(mesh_nodes, material_names),
+                    );
+                }
+                None => {}
+            }
+        }
+        expressions
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct BlendShapeGroup {
+    pub name: String,
+    #[serde(rename = "presetName")]
+    pub preset_name: Option<String>,
+    #[serde(default)]
+    pub binds: Vec<BlendShapeBind>,
+    #[serde(rename = "isBinary", default)]
+    pub is_binary: bool,
+    #[serde(rename = "materialValues", default)]
+    pub material_values: Vec<MaterialValueBind>,
+}
+
+impl BlendShapeGroup {
+    fn to_preset(
+        &self,
+        mesh_nodes: &[Option<usize>],
+        material_names: &[Option<String>],
+    ) -> VrmPreset {
+        // VRM 0.x binds material values to material names, which may be shared by several materials.
+        let materials = |bind: &MaterialValueBind| {
+            let name = bind.material_name.clone();
+            material_names
+                .iter()
+                .enumerate()
+                .filter(move |(_, material)| material.as_ref() == Some(&name))
+                .map(|(material, _)| material)
+        };
+        VrmPreset {
+            is_binary: self.is_binary,
+            morph_target_binds: Some(
+                self.binds
+                    .iter()
+                    .flat_map(|bind| {
+                        mesh_nodes
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, mesh)| **mesh == Some(bind.mesh))
+                            .map(|(node, _)| MorphTargetBind {
+                                index: bind.index,
+                                node,
+                                // VRM 0.x weights range from 0 to 100.
+                                weight: bind.weight / 100.,
+                            })
+                    })
+                    .collect(),
+            ),
+            material_color_binds: Some(
+                self.material_values
+                    .iter()
+                    .filter_map(|bind| Some((bind, bind.color_type()?, bind.linear_color()?)))
+                    .flat_map(|(bind, ty, target_value)| {
+                        materials(bind).map(move |material| MaterialColorBind {
+                            material,
+                            ty,
+                            target_value,
+                        })
+                    })
+                    .collect(),
+            ),
+            texture_transform_binds: Some(
+                self.material_values
+                    .iter()
+                    .filter_map(|bind| Some((bind, bind.texture_transform()?)))
+                    .flat_map(|(bind, (scale, offset))| {
+                        materials(bind).map(move |material| TextureTransformBind {
+                            material,
+                            scale,
+                            offset,
+                        })
+                    })
+                    .collect(),
+            ),
+            override_blink: ExpressionOverride::None,
+            override_look_at: ExpressionOverride::None,
+            override_mouth: ExpressionOverride::None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub struct BlendShapeBind {
+    /// The index of the mesh, not the node.
+    pub mesh: usize,
+    pub index: usize,
+    pub weight: f32,
+}
+
+/// Animates a property of the materials of the name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaterialValueBind {
+    #[serde(rename = "materialName")]
+    pub material_name: String,
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+    #[serde(rename = "targetValue")]
+    pub target_value: Vec<f32>,
+}
+
+impl MaterialValueBind {
+    fn color_type(&self) -> Option<MaterialColorType> {
+        match self.property_name.as_str() {
+            "_Color" => Some(MaterialColorType::Color),
+            "_EmissionColor" => Some(MaterialColorType::EmissionColor),
+            "_ShadeColor" => Some(MaterialColorType::ShadeColor),
+            "_RimColor" => Some(MaterialColorType::RimColor),
+            "_OutlineColor" => Some(MaterialColorType::OutlineColor),
+            _ => None,
+        }
+    }
+
+    /// Returns the target color in linear space.
+    ///
+    /// VRM 0.x stores the colors in gamma space as Unity does, while VRM 1.0 expects linear colors.
+    fn linear_color(&self) -> Option<[f32; 4]> {
+        let [r, g, b, a] = self.target_value.get(..4)?.try_into().ok()?;
+        Some(LinearRgba::from(Srgba::new(r, g, b, a)).to_f32_array())
+    }
+
+    /// Returns the scale and the offset of `_MainTex_ST`.
+    ///
+    /// The V axis of Unity points up, so the offset is flipped into the glTF UV space.
+    fn texture_transform(&self) -> Option<([f32; 2], [f32; 2])> {
+        if self.property_name != "_MainTex_ST" {
+            return None;
+        }
+        let [scale_x, scale_y, offset_x, offset_y] = self.target_value.get(..4)?.try_into().ok()?;
+        Some(([scale_x, scale_y], [offset_x, 1. - scale_y - offset_y]))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SecondaryAnimation {
+    #[serde(rename = "boneGroups", default)]
+    pub bone_groups: Vec<BoneGroup>,
+    #[serde(rename = "colliderGroups", default)]
+    pub collider_groups: Vec<Vrm0ColliderGroup>,
+}
+
+impl SecondaryAnimation {
+    fn to_spring_bone(
+        &self,
+        node_children: &[Vec<usize>],
+    ) -> VRMCSpringBone {
+        let mut colliders = Vec::new();
+        let mut collider_groups = Vec::new();
+        for (i, group) in self.collider_groups.iter().enumerate() {
+            let start = colliders.len() as u64;
+            colliders.extend(group.colliders.iter().map(|collider| Collider {
+                node: group.node,
+                shape: ColliderShape::Sphere(Sphere {
+                    offset: collider.offset.rotated(),
+                    radius: collider.radius,
+                }),
+                extensions: None,
+            }));
+            collider_groups.push(ColliderGroup {
+                name: format!("colliderGroup{i}"),
+                colliders: (start..colliders.len() as u64).collect(),
+            });
+        }
+
+        VRMCSpringBone {
+            spec_version: "1.0".to_string(),
+            colliders,
+            collider_groups,
+            springs: self
+                .bone_groups
+                .iter()
+                .flat_map(|group| group.to_springs(node_children))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct BoneGroup {
+    pub comment: Option<String>,
+    /// The misspelling is the original key of VRM 0.x.
+    #[serde(rename = "stiffiness", default)]
+    pub stiffness: f32,
+    #[serde(rename = "gravityPower", default)]
+    pub gravity_power: f32,
+    #[serde(rename = "gravityDir")]
+    pub gravity_dir: Option<Vrm0Vec3>,
+    #[serde(rename = "dragForce", default)]
+    pub drag_force: f32,
+    /// The node index of the center, or `-1` if the group has no center.
+    pub center: Option<i64>,
+    #[serde(rename = "hitRadius", default)]
+    pub hit_radius: f32,
+    /// The node indices of the root bones.
+    #[serde(default)]
+    pub bones: Vec<usize>,
+    #[serde(rename = "colliderGroups", default)]
+    pub collider_groups: Vec<usize>,
+}
+
+impl BoneGroup {
+    /// VRM 0.x shakes every descendant of the root bones,
+    /// so each root is expanded into the chains following its first child,
+    /// and the other children start chains of their own.
+    fn to_springs(
+        &self,
+        node_children: &[Vec<usize>],
+    ) -> Vec<Spring> {
+        let gravity_dir = self
+            .gravity_dir
+            .map(|dir| dir.rotated())
+            .unwrap_or([0., -1., 0.]);
+        let mut springs = Vec::new();
+        let mut heads = self.bones.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(head) = heads.pop() {
+            let mut chain = vec![head];
+            let mut node = head;
+            while let Some((first, others)) = node_children
+                .get(node)
+                .and_then(|children| children.split_first())
+            {
+                heads.extend(others.iter().rev());
+                chain.push(*first);
+                node = *first;
+            }
+            springs.push(Spring {
+                name: self.comment.clone().unwrap_or_default(),
+                joints: chain
+                    .into_iter()
+                    .map(|node| SpringJoint {
+                        node,
+                        drag_force: Some(self.drag_force),
+                        gravity_dir: Some(gravity_dir),
+                        gravity_power: Some(self.gravity_power),
+                        hit_radius: Some(self.hit_radius),
+                        stiffness: Some(self.stiffness),
+                        extensions: None,
+                    })
+                    .collect(),
+                collider_groups: Some(self.collider_groups.clone()),
+                center: self.center.and_then(|center| usize::try_from(center).ok()),
+            });
+        }
+        springs
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Vrm0ColliderGroup {
+    pub node: usize,
+    #[serde(default)]
+    pub colliders: Vec<Vrm0Collider>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Vrm0Collider {
+    pub offset: Vrm0Vec3,
+    pub radius: f32,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Copy, Clone)]
+pub struct Vrm0Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vrm0Vec3 {
+    /// Rotates 180 degrees around the Y axis in the same way as the nodes are migrated.
+    #[inline]
+    fn rotated(self) -> [f32; 3] {
+        [-self.x, self.y, -self.z]
+    }
+}
+
+/// The thumb bones were renamed in VRM 1.0; other bones keep their names.
+fn convert_bone_name(bone: &str) -> &str {
+    match bone {
+        "leftThumbProximal" => "leftThumbMetacarpal",
+        "leftThumbIntermediate" => "leftThumbProximal",
+        "rightThumbProximal" => "rightThumbMetacarpal",
+        "rightThumbIntermediate" => "rightThumbProximal",
+        other => other,
+    }
+}
+
+fn convert_preset_name(preset_name: &str) -> Option<&'static str> {
+    match preset_name.to_lowercase().as_str() {
+        "neutral" => Some("neutral"),
+        "joy" => Some("happy"),
+        "angry" => Some("angry"),
+        "sorrow" => Some("sad"),
+        "fun" => Some("relaxed"),
+        "a" => Some("aa"),
+        "i" => Some("ih"),
+        "u" => Some("ou"),
+        "e" => Some("ee"),
+        "o" => Some("oh"),
+        "blink" => Some("blink"),
+        "blink_l" => Some("blinkLeft"),
+        "blink_r" => Some("blinkRight"),
+        "lookup" => Some("lookUp"),
+        "lookdown" => Some("lookDown"),
+        "lookleft" => Some("lookLeft"),
+        "lookright" => Some("lookRight"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::extensions::vrm0::Vrm0;
+    use crate::vrm::extensions::vrmc_spring_bone::{ColliderShape, Sphere};
+    use crate::vrm::extensions::vrmc_vrm::MaterialColorType;
+
+    #[test]
+    fn convert_thumb_bones() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(
+            r#"{
+                "humanoid": {
+                    "humanBones": [
+                        { "bone": "hips", "node": 1 },
+                        { "bone": "leftThumbProximal", "node": 2 },
+                        { "bone": "leftThumbIntermediate", "node": 3 }
+                    ]
+                }
+            }"#,
+        )?;
+        let bones = vrm0.to_vrmc_vrm(&[], &[]).humanoid.human_bones;
+        assert_eq!(bones["hips"].node, 1);
+        assert_eq!(bones["leftThumbMetacarpal"].node, 2);
+        assert_eq!(bones["leftThumbProximal"].node, 3);
+        success!()
+    }
+
+    #[test]
+    fn convert_blend_shape_groups() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(
+            r#"{
+                "humanoid": { "humanBones": [] },
+                "blendShapeMaster": {
+                    "blendShapeGroups": [
+                        {
+                            "name": "Joy",
+                            "presetName": "joy",
+                            "binds": [{ "mesh": 0, "index": 3, "weight": 50 }],
+                            "isBinary": true
+                        },
+                        { "name": "Custom", "presetName": "unknown", "binds": [] }
+                    ]
+                }
+            }"#,
+        )?;
+        let expressions = vrm0.to_vrmc_vrm(&[None, Some(0)], &[]).expressions.unwrap();
+        assert_eq!(expressions.preset.len(), 1);
+        assert!(expressions.custom.contains_key("Custom"));
+
+        let happy = &expressions.preset["happy"];
+        assert!(happy.is_binary);
+        let binds = happy.morph_target_binds.as_ref().unwrap();
+        assert_eq!(binds.len(), 1);
+        assert_eq!(binds[0].node, 1);
+        assert_eq!(binds[0].index, 3);
+        assert!((binds[0].weight - 0.5).abs() < f32::EPSILON);
+        success!()
+    }
+
+    #[test]
+    fn convert_material_values() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(
+            r#"{
+                "humanoid": { "humanBones": [] },
+                "blendShapeMaster": {
+                    "blendShapeGroups": [{
+                        "name": "Joy",
+                        "presetName": "joy",
+                        "materialValues": [
+                            { "materialName": "Face", "propertyName": "_Color", "targetValue": [1, 0.5, 0, 1] },
+                            { "materialName": "Face", "propertyName": "_MainTex_ST", "targetValue": [0.5, 0.5, 0.25, 0] },
+                            { "materialName": "Face", "propertyName": "_Unknown", "targetValue": [1, 1, 1, 1] },
+                            { "materialName": "Missing", "propertyName": "_Color", "targetValue": [1, 1, 1, 1] }
+                        ]
+                    }]
+                }
+            }"#,
+        )?;
+        let names = [Some("Body".to_string()), Some("Face".to_string()), None];
+        let expressions = vrm0.to_vrmc_vrm(&[], &names).expressions.unwrap();
+        let happy = &expressions.preset["happy"];
+
+        let colors = happy.material_color_binds.as_ref().unwrap();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].material, 1);
+        assert_eq!(colors[0].ty, MaterialColorType::Color);
+        let [r, g, b, a] = colors[0].target_value;
+        assert!((r - 1.).abs() < 1e-5 && (g - 0.214).abs() < 1e-3 && b.abs() < 1e-5);
+        assert!((a - 1.).abs() < 1e-5);
+
+        let transforms = happy.texture_transform_binds.as_ref().unwrap();
+        assert_eq!(transforms.len(), 1);
+        assert_eq!(transforms[0].material, 1);
+        assert_eq!(transforms[0].scale, [0.5, 0.5]);
+        assert_eq!(transforms[0].offset, [0.25, 0.5]);
+        success!()
+    }
+
+    #[test]
+    fn expand_bone_groups_into_chains() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(
+            r#"{
+                "humanoid": { "humanBones": [] },
+                "secondaryAnimation": {
+                    "boneGroups": [{
+                        "comment": "hair",
+                        "stiffiness": 0.8,
+                        "gravityPower": 0.1,
+                        "gravityDir": { "x": 1, "y": -1, "z": 1 },
+                        "dragForce": 0.4,
+                        "center": -1,
+                        "hitRadius": 0.02,
+                        "bones": [0],
+                        "colliderGroups": [0]
+                    }]
+                }
+            }"#,
+        )?;
+        // 0 -> 1 -> 2
+        //   -> 3
+        let children = vec![vec![1, 3], vec![2], vec![], vec![]];
+        let spring_bone = vrm0.to_vrmc_spring_bone(&children).unwrap();
+
+        let chains = spring_bone
+            .springs
+            .iter()
+            .map(|spring| spring.joints.iter().map(|j| j.node).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(chains, vec![vec![0, 1, 2], vec![3]]);
+
+        let spring = &spring_bone.springs[0];
+        assert_eq!(spring.name, "hair");
+        assert_eq!(spring.center, None);
+        assert_eq!(spring.collider_groups, Some(vec![0]));
+        let joint = spring.joints[0];
+        assert_eq!(joint.stiffness, Some(0.8));
+        assert_eq!(joint.drag_force, Some(0.4));
+        assert_eq!(joint.gravity_dir, Some([-1., -1., -1.]));
+        success!()
+    }
+
+    #[test]
+    fn convert_collider_groups() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(
+            r#"{
+                "humanoid": { "humanBones": [] },
+                "secondaryAnimation": {
+                    "boneGroups": [],
+                    "colliderGroups": [
+                        {
+                            "node": 5,
+                            "colliders": [
+                                { "offset": { "x": 1, "y": 2, "z": 3 }, "radius": 0.1 },
+                                { "offset": { "x": 0, "y": 0, "z": 0 }, "radius": 0.2 }
+                            ]
+                        },
+                        {
+                            "node": 6,
+                            "colliders": [{ "offset": { "x": 0, "y": 0, "z": 0 }, "radius": 0.3 }]
+                        }
+                    ]
+                }
+            }"#,
+        )?;
+        let spring_bone = vrm0.to_vrmc_spring_bone(&[]).unwrap();
+
+        assert_eq!(spring_bone.colliders.len(), 3);
+        assert_eq!(spring_bone.colliders[0].node, 5);
+        assert_eq!(
+            spring_bone.colliders[0].shape,
+            ColliderShape::Sphere(Sphere {
+                offset: [-1., 2., -3.],
+                radius: 0.1,
+            })
+        );
+        assert_eq!(spring_bone.collider_groups[0].colliders, vec![0, 1]);
+        assert_eq!(spring_bone.collider_groups[1].colliders, vec![2]);
+        success!()
+    }
+}
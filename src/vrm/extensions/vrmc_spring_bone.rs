@@ -55,6 +55,92 @@ pub struct ColliderGroup {
 pub struct Collider {
     pub node: usize,
     pub shape: ColliderShape,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<ColliderExtensions>,
+}
+
+impl Collider {
+    /// Returns the shape of [`ExtendedCollider`] if it exists, otherwise the base shape.
+    pub fn resolved_shape(&self) -> SpringColliderShape {
+        self.extensions
+            .and_then(|extensions| extensions.extended_collider)
+            .map_or_else(
+                || SpringColliderShape::from(self.shape),
+                |extended| SpringColliderShape::from(extended.shape),
+            )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default)]
+pub struct ColliderExtensions {
+    #[serde(rename = "VRMC_springBone_extended_collider")]
+    pub extended_collider: Option<ExtendedCollider>,
+}
+
+/// The [`VRMC_springBone_extended_collider`](https://github.com/vrm-c/vrm-specification/tree/master/specification/VRMC_springBone_extended_collider-1.0) extension.
+///
+/// This overrides the base shape of the collider.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub struct ExtendedCollider {
+    pub shape: ExtendedColliderShape,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtendedColliderShape {
+    Sphere(ExtendedSphere),
+    Capsule(ExtendedCapsule),
+    Plane(Plane),
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+pub struct ExtendedSphere {
+    pub offset: [f32; 3],
+    pub radius: f32,
+    /// If `true`, the joints are kept inside the sphere.
+    #[serde(default)]
+    pub inside: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+pub struct ExtendedCapsule {
+    pub offset: [f32; 3],
+    pub radius: f32,
+    pub tail: [f32; 3],
+    /// If `true`, the joints are kept inside the capsule.
+    #[serde(default)]
+    pub inside: bool,
+}
+
+impl From<ExtendedColliderShape> for SpringColliderShape {
+    fn from(shape: ExtendedColliderShape) -> Self {
+        match shape {
+            ExtendedColliderShape::Sphere(sphere) => {
+                let base = Sphere {
+                    offset: sphere.offset,
+                    radius: sphere.radius,
+                };
+                if sphere.inside {
+                    Self::InsideSphere(base)
+                } else {
+                    Self::Sphere(base)
+                }
+            }
+            ExtendedColliderShape::Capsule(capsule) => {
+                let base = Capsule {
+                    offset: capsule.offset,
+                    radius: capsule.radius,
+                    tail: capsule.tail,
+                };
+                if capsule.inside {
+                    Self::InsideCapsule(base)
+                } else {
+                    Self::Capsule(base)
+                }
+            }
+            ExtendedColliderShape::Plane(plane) => Self::Plane(plane),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -85,12 +171,135 @@ pub struct SpringJoint {
     #[serde(rename = "hitRadius")]
     pub hit_radius: Option<f32>,
     pub stiffness: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<SpringJointExtensions>,
 }
 
-/// The shape of the collision detection for [Collider]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default)]
+pub struct SpringJointExtensions {
+    #[serde(rename = "VRMC_springBone_limit")]
+    pub limit: Option<SpringBoneLimit>,
+}
+
+/// The `VRMC_springBone_limit` extension.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct SpringBoneLimit {
+    pub limit: SpringJointLimit,
+}
+
+/// The limit of the direction of a joint, defined in `VRMC_springBone_limit`.
+///
+/// The limit is evaluated in the limit space, which is the initial local space of the joint rotated by `rotation`.
+/// In the limit space, the center of the limit is the direction from the joint to the tail in the initial pose.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Component, Reflect)]
 #[reflect(Component, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub enum SpringJointLimit {
+    /// The tail stays within `angle` from the center.
+    Cone(ConeLimit),
+
+    /// The tail only rotates around the X axis of the limit space, within `angle` from the center.
+    Hinge(HingeLimit),
+
+    /// The tail rotates within `pitch` around the X axis and `yaw` around the other axis of the limit space.
+    Spherical(SphericalLimit),
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct ConeLimit {
+    /// The maximum angle in radians.
+    pub angle: f32,
+    #[serde(default = "identity_rotation")]
+    pub rotation: [f32; 4],
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct HingeLimit {
+    /// The maximum angle in radians.
+    pub angle: f32,
+    #[serde(default = "identity_rotation")]
+    pub rotation: [f32; 4],
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Reflect)]
+pub struct SphericalLimit {
+    /// The maximum angle around the X axis in radians.
+    pub pitch: f32,
+    /// The maximum angle around the other axis in radians.
+    pub yaw: f32,
+    #[serde(default = "identity_rotation")]
+    pub rotation: [f32; 4],
+}
+
+const fn identity_rotation() -> [f32; 4] {
+    [0., 0., 0., 1.]
+}
+
+impl SpringJointLimit {
+    /// Returns the direction from the joint to the tail constrained by the limit.
+    ///
+    /// `joint_rotation` is the global rotation of the joint in the initial pose relative to its parent,
+    /// and `bone_axis` is the direction to the tail in the initial local space of the joint.
+    pub fn constrain(
+        &self,
+        joint_rotation: Quat,
+        bone_axis: Vec3,
+        dir: Vec3,
+    ) -> Vec3 {
+        let rotation = Quat::from_array(match self {
+            Self::Cone(cone) => cone.rotation,
+            Self::Hinge(hinge) => hinge.rotation,
+            Self::Spherical(spherical) => spherical.rotation,
+        })
+        .normalize();
+        let space = joint_rotation * rotation;
+        let dir = space.inverse() * dir;
+        let center = rotation.inverse() * bone_axis.normalize();
+        // The pitch axis is the X axis made perpendicular to the center.
+        let x = (Vec3::X - center * center.x)
+            .try_normalize()
+            .unwrap_or_else(|| center.any_orthonormal_vector());
+        let y = center.cross(x);
+        let constrained = match self {
+            Self::Cone(cone) => {
+                if dir.angle_between(center) <= cone.angle {
+                    dir
+                } else {
+                    let side = (dir - center * dir.dot(center))
+                        .try_normalize()
+                        .unwrap_or(y);
+                    center * cone.angle.cos() + side * cone.angle.sin()
+                }
+            }
+            Self::Hinge(hinge) => {
+                let angle = dir
+                    .dot(y)
+                    .atan2(dir.dot(center))
+                    .clamp(-hinge.angle, hinge.angle);
+                center * angle.cos() + y * angle.sin()
+            }
+            Self::Spherical(spherical) => {
+                // The angles are clamped below the right angle to keep the tail in front of the center.
+                let max_pitch = spherical.pitch.min(MAX_SPHERICAL_ANGLE);
+                let max_yaw = spherical.yaw.min(MAX_SPHERICAL_ANGLE);
+                let pitch = dir
+                    .dot(y)
+                    .atan2(dir.dot(center))
+                    .clamp(-max_pitch, max_pitch);
+                let yaw = dir.dot(x).atan2(dir.dot(center)).clamp(-max_yaw, max_yaw);
+                (center + y * pitch.tan() + x * yaw.tan()).normalize()
+            }
+        };
+        space * constrained
+    }
+}
+
+const MAX_SPHERICAL_ANGLE: f32 = 89. * std::f32::consts::PI / 180.;
+
+/// The shape of the collision detection for [Collider]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Reflect)]
+#[reflect(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ColliderShape {
     Sphere(Sphere),
     Capsule(Capsule),
@@ -102,7 +311,41 @@ impl Default for ColliderShape {
     }
 }
 
-impl ColliderShape {
+/// The shape of a spring bone collider in the world.
+///
+/// In addition to the base shapes of [`ColliderShape`], this covers the shapes of [`ExtendedColliderShape`]
+/// and is also used by colliders outside of the VRM.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpringColliderShape {
+    Sphere(Sphere),
+    Capsule(Capsule),
+    Plane(Plane),
+
+    /// The sphere that keeps the joints inside, defined in `VRMC_springBone_extended_collider`.
+    InsideSphere(Sphere),
+
+    /// The capsule that keeps the joints inside, defined in `VRMC_springBone_extended_collider`.
+    InsideCapsule(Capsule),
+}
+
+impl From<ColliderShape> for SpringColliderShape {
+    fn from(shape: ColliderShape) -> Self {
+        match shape {
+            ColliderShape::Sphere(sphere) => Self::Sphere(sphere),
+            ColliderShape::Capsule(capsule) => Self::Capsule(capsule),
+        }
+    }
+}
+
+impl Default for SpringColliderShape {
+    fn default() -> Self {
+        Self::Sphere(Sphere::default())
+    }
+}
+
+impl SpringColliderShape {
     /// Returns the collision vector from the collider to the target position.
     pub fn calc_collision(
         &self,
@@ -119,9 +362,35 @@ impl ColliderShape {
                 let distance = delta.norm() - sphere.radius - joint_radius;
                 (delta.normalize(), distance)
             }
-            Self::Capsule(_) => {
-                //TODO: In UniVRM, only SphereCollider is implemented, so it seems to be postponed
-                (Vec3::ZERO, 1.)
+            Self::Capsule(capsule) => {
+                let matrix = collider.compute_matrix();
+                let head = matrix.transform_point3(Vec3::from(capsule.offset));
+                let tail = matrix.transform_point3(Vec3::from(capsule.tail));
+                let delta = next_tail - closest_point_on_segment(head, tail, next_tail);
+                let distance = delta.norm() - capsule.radius - joint_radius;
+                (segment_normal(delta, tail - head), distance)
+            }
+            Self::Plane(plane) => {
+                let point = collider.transform_point(Vec3::from(plane.offset));
+                let normal = (collider.rotation() * Vec3::from(plane.normal)).normalize();
+                let distance = (next_tail - point).dot(normal) - joint_radius;
+                (normal, distance)
+            }
+            Self::InsideSphere(sphere) => {
+                let offset = collider
+                    .compute_matrix()
+                    .transform_point3(Vec3::from(sphere.offset));
+                let delta = next_tail - offset;
+                let distance = sphere.radius - joint_radius - delta.norm();
+                (-delta.normalize(), distance)
+            }
+            Self::InsideCapsule(capsule) => {
+                let matrix = collider.compute_matrix();
+                let head = matrix.transform_point3(Vec3::from(capsule.offset));
+                let tail = matrix.transform_point3(Vec3::from(capsule.tail));
+                let delta = next_tail - closest_point_on_segment(head, tail, next_tail);
+                let distance = capsule.radius - joint_radius - delta.norm();
+                (-segment_normal(delta, tail - head), distance)
             }
         }
     }
@@ -129,12 +398,46 @@ impl ColliderShape {
     #[inline]
     pub const fn radius(&self) -> f32 {
         match self {
-            Self::Sphere(sphere) => sphere.radius,
-            Self::Capsule(capsule) => capsule.radius,
+            Self::Sphere(sphere) | Self::InsideSphere(sphere) => sphere.radius,
+            Self::Capsule(capsule) | Self::InsideCapsule(capsule) => capsule.radius,
+            Self::Plane(_) => 0.,
         }
     }
 }
 
+/// Returns the direction of `delta` from a line segment along `segment`.
+///
+/// If the point lies on the segment, a direction perpendicular to the segment is returned instead,
+/// or [`Vec3::Y`] if the segment is also degenerate.
+#[inline]
+fn segment_normal(
+    delta: Vec3,
+    segment: Vec3,
+) -> Vec3 {
+    delta.try_normalize().unwrap_or_else(|| {
+        segment
+            .try_normalize()
+            .map(|axis| axis.any_orthonormal_vector())
+            .unwrap_or(Vec3::Y)
+    })
+}
+
+/// Returns the closest point to `point` on the line segment from `head` to `tail`.
+#[inline]
+fn closest_point_on_segment(
+    head: Vec3,
+    tail: Vec3,
+    point: Vec3,
+) -> Vec3 {
+    let segment = tail - head;
+    let length_squared = segment.length_squared();
+    if length_squared <= f32::EPSILON {
+        return head;
+    }
+    let t = (point - head).dot(segment) / length_squared;
+    head + segment * t.clamp(0., 1.)
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Component, Reflect, Default)]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct Sphere {
@@ -144,7 +447,7 @@ pub struct Sphere {
     pub radius: f32,
 }
 
-/// 楕円形の
+/// The capsule shape, which is a line segment from `offset` to `tail` with `radius`.
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Component, Reflect, Default)]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct Capsule {
@@ -156,11 +459,38 @@ pub struct Capsule {
     pub tail: [f32; 3],
 }
 
+/// The infinite plane that pushes the joints to the side of `normal`.
+///
+/// This shape is defined in `VRMC_springBone_extended_collider`, and is also used by colliders outside of the VRM.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Plane {
+    /// Local coordinate of a point on the plane
+    pub offset: [f32; 3],
+    /// Local direction of the normal of the plane
+    pub normal: [f32; 3],
+}
+
+impl Default for Plane {
+    fn default() -> Self {
+        Self {
+            offset: [0., 0., 0.],
+            normal: [0., 1., 0.],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::success;
     use crate::tests::TestResult;
-    use crate::vrm::extensions::vrmc_spring_bone::VRMCSpringBone;
+    use crate::vrm::extensions::vrmc_spring_bone::{
+        Capsule, Collider, ConeLimit, HingeLimit, Plane, Sphere, SphericalLimit,
+        SpringColliderShape, SpringJoint, SpringJointLimit, VRMCSpringBone,
+    };
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{GlobalTransform, Transform};
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
 
     #[test]
     fn deserialize_vrmc_spring_bone() -> TestResult {
@@ -168,4 +498,238 @@ mod tests {
             serde_json::from_str(include_str!("./vrmc_spring_bone.json"))?;
         success!()
     }
+
+    #[test]
+    fn sphere_collision() {
+        let shape = SpringColliderShape::Sphere(Sphere {
+            offset: [0., 1., 0.],
+            radius: 0.5,
+        });
+        let collider = GlobalTransform::from(Transform::from_xyz(1., 0., 0.));
+        let (dir, distance) = shape.calc_collision(Vec3::new(1., 1., 0.6), &collider, 0.2);
+        assert!(dir.abs_diff_eq(Vec3::Z, 1e-5));
+        assert!((distance - -0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_collision_with_cylinder() {
+        let shape = SpringColliderShape::Capsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(0.6, 1., 0.), &GlobalTransform::IDENTITY, 0.2);
+        assert!(dir.abs_diff_eq(Vec3::X, 1e-5));
+        assert!((distance - -0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_collision_beyond_head() {
+        let shape = SpringColliderShape::Capsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(0., -1., 0.), &GlobalTransform::IDENTITY, 0.);
+        assert!(dir.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        assert!((distance - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_collision_beyond_tail_in_collider_space() {
+        let shape = SpringColliderShape::Capsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let collider = GlobalTransform::from(Transform::from_xyz(0., 1., 0.));
+        let (dir, distance) = shape.calc_collision(Vec3::new(0., 3.25, 0.), &collider, 0.);
+        assert!(dir.abs_diff_eq(Vec3::Y, 1e-5));
+        assert!((distance - -0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_collision_on_axis() {
+        let shape = SpringColliderShape::Capsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(0., 1., 0.), &GlobalTransform::IDENTITY, 0.);
+        assert!(dir.is_normalized());
+        assert!(dir.dot(Vec3::Y).abs() < 1e-5);
+        assert!((distance - -0.5).abs() < 1e-5);
+
+        let degenerate = SpringColliderShape::Capsule(Capsule {
+            offset: [0., 1., 0.],
+            radius: 0.5,
+            tail: [0., 1., 0.],
+        });
+        let (dir, distance) =
+            degenerate.calc_collision(Vec3::new(0., 1., 0.), &GlobalTransform::IDENTITY, 0.);
+        assert_eq!(dir, Vec3::Y);
+        assert!((distance - -0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn plane_collision() {
+        let shape = SpringColliderShape::Plane(Plane {
+            offset: [0., 1., 0.],
+            normal: [1., 0., 0.],
+        });
+        let collider = GlobalTransform::from(
+            Transform::from_xyz(0., 1., 0.).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+        );
+        let (dir, distance) = shape.calc_collision(Vec3::new(-0.5, 0.7, 0.), &collider, 0.1);
+        assert!(dir.abs_diff_eq(Vec3::Y, 1e-5));
+        assert!((distance - -0.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn resolve_extended_collider() -> TestResult {
+        let collider: Collider = serde_json::from_str(
+            r#"{
+                "node": 3,
+                "shape": { "sphere": { "offset": [0, 0, 0], "radius": 0.1 } },
+                "extensions": {
+                    "VRMC_springBone_extended_collider": {
+                        "specVersion": "1.0",
+                        "shape": { "sphere": { "offset": [0, 1, 0], "radius": 0.5, "inside": true } }
+                    }
+                }
+            }"#,
+        )?;
+        assert_eq!(
+            collider.resolved_shape(),
+            SpringColliderShape::InsideSphere(Sphere {
+                offset: [0., 1., 0.],
+                radius: 0.5,
+            })
+        );
+        success!()
+    }
+
+    #[test]
+    fn fall_back_to_base_shape() -> TestResult {
+        let collider: Collider = serde_json::from_str(
+            r#"{
+                "node": 3,
+                "shape": { "capsule": { "offset": [0, 0, 0], "radius": 0.1, "tail": [0, 1, 0] } }
+            }"#,
+        )?;
+        assert_eq!(
+            collider.resolved_shape(),
+            SpringColliderShape::from(collider.shape)
+        );
+        success!()
+    }
+
+    #[test]
+    fn inside_sphere_collision() {
+        let shape = SpringColliderShape::InsideSphere(Sphere {
+            offset: [0., 0., 0.],
+            radius: 1.,
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(1.2, 0., 0.), &GlobalTransform::IDENTITY, 0.1);
+        assert!(dir.abs_diff_eq(Vec3::NEG_X, 1e-5));
+        assert!((distance - -0.3).abs() < 1e-5);
+
+        let (_, distance) =
+            shape.calc_collision(Vec3::new(0.5, 0., 0.), &GlobalTransform::IDENTITY, 0.1);
+        assert!(0. < distance);
+    }
+
+    #[test]
+    fn inside_capsule_collision() {
+        let shape = SpringColliderShape::InsideCapsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(0., 1., 0.7), &GlobalTransform::IDENTITY, 0.);
+        assert!(dir.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!((distance - -0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn inside_capsule_collision_on_axis() {
+        let shape = SpringColliderShape::InsideCapsule(Capsule {
+            offset: [0., 0., 0.],
+            radius: 0.5,
+            tail: [0., 2., 0.],
+        });
+        let (dir, distance) =
+            shape.calc_collision(Vec3::new(0., 1., 0.), &GlobalTransform::IDENTITY, 0.);
+        assert!(dir.is_normalized());
+        assert!((distance - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn deserialize_joint_limit() -> TestResult {
+        let joint: SpringJoint = serde_json::from_str(
+            r#"{
+                "node": 3,
+                "extensions": {
+                    "VRMC_springBone_limit": {
+                        "limit": { "hinge": { "angle": 0.5 } }
+                    }
+                }
+            }"#,
+        )?;
+        assert_eq!(
+            joint.extensions.and_then(|e| e.limit).map(|l| l.limit),
+            Some(SpringJointLimit::Hinge(HingeLimit {
+                angle: 0.5,
+                rotation: [0., 0., 0., 1.],
+            }))
+        );
+        success!()
+    }
+
+    #[test]
+    fn cone_limit_clamps_angle() {
+        let limit = SpringJointLimit::Cone(ConeLimit {
+            angle: 0.5,
+            rotation: [0., 0., 0., 1.],
+        });
+        let dir = limit.constrain(Quat::IDENTITY, Vec3::NEG_Y, Vec3::X);
+        assert!((dir.angle_between(Vec3::NEG_Y) - 0.5).abs() < 1e-5);
+        assert!(0. < dir.x);
+
+        let inside = Vec3::new(0.1, -1., 0.).normalize();
+        assert!(limit
+            .constrain(Quat::IDENTITY, Vec3::NEG_Y, inside)
+            .abs_diff_eq(inside, 1e-5));
+    }
+
+    #[test]
+    fn hinge_limit_projects_onto_plane() {
+        let limit = SpringJointLimit::Hinge(HingeLimit {
+            angle: 1.,
+            rotation: [0., 0., 0., 1.],
+        });
+        let dir = limit.constrain(Quat::IDENTITY, Vec3::NEG_Y, Vec3::new(1., -1., 0.5));
+        assert!(dir.x.abs() < 1e-5);
+        assert!(0. < dir.z);
+
+        let dir = limit.constrain(Quat::IDENTITY, Vec3::NEG_Y, Vec3::Z);
+        assert!((dir.angle_between(Vec3::NEG_Y) - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn spherical_limit_clamps_each_axis() {
+        let limit = SpringJointLimit::Spherical(SphericalLimit {
+            pitch: 0.2,
+            yaw: FRAC_PI_2,
+            rotation: [0., 0., 0., 1.],
+        });
+        let dir = limit.constrain(Quat::IDENTITY, Vec3::NEG_Y, Vec3::new(1., -1., 1.));
+        assert!((dir.z.atan2(-dir.y) - 0.2).abs() < 1e-5);
+        assert!((dir.x.atan2(-dir.y) - FRAC_PI_4).abs() < 1e-5);
+    }
 }
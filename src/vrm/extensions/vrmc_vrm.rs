@@ -1,4 +1,5 @@
 use crate::vrm::extensions::VrmNode;
+use bevy::prelude::{Reflect, ReflectDefault, ReflectDeserialize, ReflectSerialize};
 use bevy::utils::HashMap;
 use serde::{Deserialize, Serialize};
 
@@ -17,22 +18,61 @@ pub struct VrmcVrm {
 
 #[derive(Serialize, Deserialize)]
 pub struct Expressions {
+    #[serde(default)]
     pub preset: HashMap<String, VrmPreset>,
+
+    /// The expressions defined by the author, such as `tongue_out`.
+    #[serde(default)]
+    pub custom: HashMap<String, VrmPreset>,
 }
 
+/// An expression of `VRMC_vrm`, used by both of the preset and the custom expressions.
 #[derive(Serialize, Deserialize)]
 pub struct VrmPreset {
     /// If this value is `true`, `weight` value greater than 0.5 is 1.0, otherwise 0.0.
-    #[serde(rename = "isBinary")]
+    #[serde(rename = "isBinary", default)]
     pub is_binary: bool,
     #[serde(rename = "morphTargetBinds")]
     pub morph_target_binds: Option<Vec<MorphTargetBind>>,
-    #[serde(rename = "overrideBlink")]
-    pub override_blink: String,
-    #[serde(rename = "overrideLookAt")]
-    pub override_look_at: String,
-    #[serde(rename = "overrideMouth")]
-    pub override_mouth: String,
+    #[serde(rename = "materialColorBinds", default)]
+    pub material_color_binds: Option<Vec<MaterialColorBind>>,
+    #[serde(rename = "textureTransformBinds", default)]
+    pub texture_transform_binds: Option<Vec<TextureTransformBind>>,
+    #[serde(rename = "overrideBlink", default)]
+    pub override_blink: ExpressionOverride,
+    #[serde(rename = "overrideLookAt", default)]
+    pub override_look_at: ExpressionOverride,
+    #[serde(rename = "overrideMouth", default)]
+    pub override_mouth: ExpressionOverride,
+}
+
+/// How an active expression suppresses the blink, look at or mouth expressions.
+#[derive(Serialize, Deserialize, Reflect, Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpressionOverride {
+    #[default]
+    None,
+
+    /// The overridden expressions are disabled while the weight is greater than 0.
+    Block,
+
+    /// The overridden expressions are weakened by the weight.
+    Blend,
+}
+
+impl ExpressionOverride {
+    /// Returns the rate at which the overridden expressions are suppressed by the expression of `weight`.
+    pub fn rate(
+        self,
+        weight: f32,
+    ) -> f32 {
+        match self {
+            Self::Block if 0. < weight => 1.,
+            Self::Blend => weight.clamp(0., 1.),
+            _ => 0.,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -42,6 +82,46 @@ pub struct MorphTargetBind {
     pub weight: f32,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct MaterialColorBind {
+    /// The index of the material in the glTF.
+    pub material: usize,
+    #[serde(rename = "type")]
+    pub ty: MaterialColorType,
+
+    /// The color in linear space when the expression is fully applied.
+    #[serde(rename = "targetValue")]
+    pub target_value: [f32; 4],
+}
+
+/// The property of the material driven by [`MaterialColorBind`].
+#[derive(Serialize, Deserialize, Reflect, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[reflect(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaterialColorType {
+    Color,
+    EmissionColor,
+    ShadeColor,
+    MatcapColor,
+    RimColor,
+    OutlineColor,
+}
+
+/// Animates the UV scale and offset of the material.
+#[derive(Serialize, Deserialize)]
+pub struct TextureTransformBind {
+    /// The index of the material in the glTF.
+    pub material: usize,
+    #[serde(default = "default_texture_scale")]
+    pub scale: [f32; 2],
+    #[serde(default)]
+    pub offset: [f32; 2],
+}
+
+const fn default_texture_scale() -> [f32; 2] {
+    [1., 1.]
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Humanoid {
     #[serde(rename = "humanBones")]
@@ -1,7 +1,8 @@
+use crate::vrm::migration::migrate_vrm0;
 use bevy::app::{App, Plugin};
-use bevy::asset::io::Reader;
+use bevy::asset::io::{Reader, VecReader};
 use bevy::asset::{Asset, AssetLoader, Handle, LoadContext};
-use bevy::gltf::{Gltf, GltfError, GltfLoader, GltfLoaderSettings};
+use bevy::gltf::{Gltf, GltfLoader, GltfLoaderSettings};
 use bevy::image::CompressedImageFormats;
 use bevy::prelude::{AssetApp, Component, TypePath};
 use bevy::render::renderer::RenderDevice;
@@ -45,7 +46,7 @@ struct VrmLoader(GltfLoader);
 impl AssetLoader for VrmLoader {
     type Asset = VrmAsset;
     type Settings = ();
-    type Error = GltfError;
+    type Error = anyhow::Error;
     async fn load(
         &self,
         reader: &mut dyn Reader,
@@ -56,7 +57,16 @@ impl AssetLoader for VrmLoader {
             include_source: true,
             ..default()
         };
-        let gltf = self.0.load(reader, &settings, load_context).await?;
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        // VRM 0.x is converted into VRM 1.0 so that it can be handled in the same way.
+        if let Some(migrated) = migrate_vrm0(&bytes)? {
+            bytes = migrated;
+        }
+        let gltf = self
+            .0
+            .load(&mut VecReader::new(bytes), &settings, load_context)
+            .await?;
         Ok(VrmAsset { gltf })
     }
 
@@ -0,0 +1,470 @@
+//! Migrates VRM 0.x binaries into VRM 1.0 before they are passed to the glTF loader.
+//!
+//! VRM 0.x models face `-Z` while VRM 1.0 models face `+Z`,
+//! so the geometry and the node transforms are rotated 180 degrees around the Y axis,
+//! and the `VRM` extension is converted into `VRMC_vrm` and `VRMC_springBone`.
+
+use crate::error::AppResult;
+use crate::vrm::extensions::vrm0::Vrm0;
+use anyhow::{bail, Context};
+use bevy::utils::HashSet;
+use serde_json::Value;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+const COMPONENT_FLOAT: u64 = 5126;
+
+/// Converts VRM 0.x binary into VRM 1.0.
+///
+/// Returns `None` if the binary is not a VRM 0.x model.
+pub(crate) fn migrate_vrm0(bytes: &[u8]) -> AppResult<Option<Vec<u8>>> {
+    let Some(mut glb) = Glb::parse(bytes)? else {
+        ensure_not_vrm0_json(bytes)?;
+        return Ok(None);
+    };
+    let Some(extensions) = glb.json.get("extensions").and_then(Value::as_object) else {
+        return Ok(None);
+    };
+    if extensions.contains_key("VRMC_vrm") {
+        return Ok(None);
+    }
+    let Some(vrm0) = extensions.get("VRM").cloned() else {
+        return Ok(None);
+    };
+    let has_spring_bone = extensions.contains_key("VRMC_springBone");
+    let vrm0: Vrm0 = serde_json::from_value(vrm0)?;
+
+    rotate_nodes(&mut glb.json);
+    rotate_accessors(&mut glb)?;
+
+    let vrmc_vrm =
+        serde_json::to_value(vrm0.to_vrmc_vrm(&mesh_nodes(&glb.json), &material_names(&glb.json)))?;
+    insert_extension(&mut glb.json, "VRMC_vrm", vrmc_vrm);
+    if !has_spring_bone {
+        if let Some(spring_bone) = vrm0.to_vrmc_spring_bone(&node_children(&glb.json)) {
+            let spring_bone = serde_json::to_value(spring_bone)?;
+            insert_extension(&mut glb.json, "VRMC_springBone", spring_bone);
+        }
+    }
+    Ok(Some(glb.to_bytes()?))
+}
+
+/// Fails if the bytes are VRM 0.x in glTF JSON,
+/// which is not migrated because the geometry may be stored in external buffers.
+fn ensure_not_vrm0_json(bytes: &[u8]) -> AppResult {
+    let Ok(json) = serde_json::from_slice::<Value>(bytes) else {
+        return Ok(());
+    };
+    let extensions = &json["extensions"];
+    if extensions.get("VRM").is_some() && extensions.get("VRMC_vrm").is_none() {
+        bail!("VRM 0.x in glTF JSON is not supported; convert it into binary glTF first");
+    }
+    Ok(())
+}
+
+/// Returns the mesh index referenced by each node.
+fn mesh_nodes(json: &Value) -> Vec<Option<usize>> {
+    json["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .map(|node| node.get("mesh").and_then(Value::as_u64).map(|i| i as usize))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the name of each material.
+fn material_names(json: &Value) -> Vec<Option<String>> {
+    json["materials"]
+        .as_array()
+        .map(|materials| {
+            materials
+                .iter()
+                .map(|material| material["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the children indices of each node.
+fn node_children(json: &Value) -> Vec<Vec<usize>> {
+    json["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .map(|node| {
+                    node["children"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|child| child.as_u64().map(|i| i as usize))
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn insert_extension(
+    json: &mut Value,
+    name: &str,
+    extension: Value,
+) {
+    if let Some(extensions) = json["extensions"].as_object_mut() {
+        extensions.insert(name.to_string(), extension);
+    }
+    match json["extensionsUsed"].as_array_mut() {
+        Some(used) => {
+            if !used.iter().any(|used| used == name) {
+                used.push(Value::from(name));
+            }
+        }
+        None => {
+            json["extensionsUsed"] = Value::from(vec![name]);
+        }
+    }
+}
+
+fn rotate_nodes(json: &mut Value) {
+    let Some(nodes) = json["nodes"].as_array_mut() else {
+        return;
+    };
+    for node in nodes.iter_mut().filter_map(Value::as_object_mut) {
+        // Both the translation `[x, y, z]` and the rotation `[x, y, z, w]` are negated on x and z.
+        for key in ["translation", "rotation"] {
+            if let Some(values) = node.get_mut(key).and_then(Value::as_array_mut) {
+                negate_xz(values);
+            }
+        }
+        if let Some(matrix) = node.get_mut("matrix").and_then(Value::as_array_mut) {
+            for (i, value) in matrix.iter_mut().enumerate() {
+                if matrix_sign(i) < 0. {
+                    negate(value);
+                }
+            }
+        }
+    }
+}
+
+fn rotate_accessors(glb: &mut Glb) -> AppResult {
+    let mut vectors = HashSet::new();
+    let mut matrices = HashSet::new();
+    for primitive in glb.json["meshes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|mesh| mesh["primitives"].as_array().into_iter().flatten())
+    {
+        let targets = primitive["targets"].as_array().into_iter().flatten();
+        for attributes in std::iter::once(&primitive["attributes"]).chain(targets) {
+            for key in ["POSITION", "NORMAL", "TANGENT"] {
+                if let Some(accessor) = attributes.get(key).and_then(Value::as_u64) {
+                    vectors.insert(accessor as usize);
+                }
+            }
+        }
+    }
+    for skin in glb.json["skins"].as_array().into_iter().flatten() {
+        if let Some(accessor) = skin.get("inverseBindMatrices").and_then(Value::as_u64) {
+            matrices.insert(accessor as usize);
+        }
+    }
+
+    // Accessors may alias the same data, which must be negated only once.
+    let mut rotated = HashSet::new();
+    for accessor in vectors {
+        glb.rotate_accessor(accessor, |i| i == 0 || i == 2, &mut rotated)?;
+    }
+    for accessor in matrices {
+        glb.rotate_accessor(accessor, |i| matrix_sign(i) < 0., &mut rotated)?;
+    }
+    Ok(())
+}
+
+/// Returns the sign of the element of `S * M * S` where `S` is the 180 degrees rotation matrix around the Y axis.
+///
+/// The matrix is column-major.
+#[inline]
+fn matrix_sign(index: usize) -> f32 {
+    let sign = |i: usize| if i == 0 || i == 2 { -1. } else { 1. };
+    sign(index % 4) * sign(index / 4)
+}
+
+fn negate_xz(values: &mut [Value]) {
+    for i in [0, 2] {
+        if let Some(value) = values.get_mut(i) {
+            negate(value);
+        }
+    }
+}
+
+fn negate(value: &mut Value) {
+    if let Some(v) = value.as_f64() {
+        *value = Value::from(-v);
+    }
+}
+
+struct Glb {
+    json: Value,
+    bin: Vec<u8>,
+}
+
+impl Glb {
+    /// Returns `None` if the bytes are not binary glTF.
+    fn parse(bytes: &[u8]) -> AppResult<Option<Self>> {
+        if bytes.len() < 12 || read_u32(bytes, 0)? != GLB_MAGIC {
+            return Ok(None);
+        }
+        let mut json = None;
+        let mut bin = Vec::new();
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let length = read_u32(bytes, offset)? as usize;
+            let chunk_type = read_u32(bytes, offset + 4)?;
+            let data = bytes
+                .get(offset + 8..offset + 8 + length)
+                .context("The glb chunk is out of range")?;
+            match chunk_type {
+                CHUNK_JSON => json = Some(serde_json::from_slice(data)?),
+                CHUNK_BIN => bin = data.to_vec(),
+                _ => {}
+            }
+            offset += 8 + length;
+        }
+        Ok(Some(Self {
+            json: json.context("Not found the json chunk in glb")?,
+            bin,
+        }))
+    }
+
+    fn to_bytes(&self) -> AppResult<Vec<u8>> {
+        let mut json = serde_json::to_vec(&self.json)?;
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut bin = self.bin.clone();
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let mut total = 12 + 8 + json.len();
+        if !bin.is_empty() {
+            total += 8 + bin.len();
+        }
+        let mut bytes = Vec::with_capacity(total);
+        bytes.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&(total as u32).to_le_bytes());
+        bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+        bytes.extend_from_slice(&json);
+        if !bin.is_empty() {
+            bytes.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+            bytes.extend_from_slice(&bin);
+        }
+        Ok(bytes)
+    }
+
+    /// Negates the float components of the accessor at which `should_negate` returns `true`.
+    ///
+    /// `should_negate` receives the component index in the element.
+    /// `rotated` holds the pairs of the buffer view and the byte offset already negated, which are skipped.
+    fn rotate_accessor(
+        &mut self,
+        accessor_index: usize,
+        should_negate: impl Fn(usize) -> bool,
+        rotated: &mut HashSet<(usize, usize)>,
+    ) -> AppResult {
+        let accessor = &self.json["accessors"][accessor_index];
+        if accessor["componentType"].as_u64() != Some(COMPONENT_FLOAT) {
+            return Ok(());
+        }
+        let components = match accessor["type"].as_str() {
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            Some("MAT4") => 16,
+            _ => return Ok(()),
+        };
+        let count = accessor["count"].as_u64().unwrap_or_default() as usize;
+        let accessor_offset = accessor["byteOffset"].as_u64().unwrap_or_default() as usize;
+
+        let mut regions = Vec::new();
+        if let Some(view) = accessor.get("bufferView").and_then(Value::as_u64) {
+            regions.push((view as usize, accessor_offset, count));
+        }
+        let sparse = &accessor["sparse"];
+        if let Some(view) = sparse["values"]["bufferView"].as_u64() {
+            let offset = sparse["values"]["byteOffset"].as_u64().unwrap_or_default() as usize;
+            let count = sparse["count"].as_u64().unwrap_or_default() as usize;
+            regions.push((view as usize, offset, count));
+        }
+
+        for (view_index, offset, count) in regions {
+            if !rotated.insert((view_index, offset)) {
+                continue;
+            }
+            let view = &self.json["bufferViews"][view_index];
+            if view["buffer"].as_u64().unwrap_or_default() != 0 {
+                continue;
+            }
+            let view_offset = view["byteOffset"].as_u64().unwrap_or_default() as usize;
+            let stride = view["byteStride"]
+                .as_u64()
+                .map(|stride| stride as usize)
+                .unwrap_or(components * 4);
+            for element in 0..count {
+                for component in (0..components).filter(|c| should_negate(*c)) {
+                    let at = view_offset + offset + element * stride + component * 4;
+                    let bytes = self
+                        .bin
+                        .get_mut(at..at + 4)
+                        .context("The accessor is out of range of the buffer")?;
+                    let value = -f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    bytes.copy_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+
+        let accessor = &mut self.json["accessors"][accessor_index];
+        if let (Some(min), Some(max)) = (accessor["min"].as_array(), accessor["max"].as_array()) {
+            let (mut min, mut max) = (min.clone(), max.clone());
+            for i in (0..min.len().min(max.len())).filter(|i| should_negate(*i)) {
+                std::mem::swap(&mut min[i], &mut max[i]);
+                negate(&mut min[i]);
+                negate(&mut max[i]);
+            }
+            accessor["min"] = Value::from(min);
+            accessor["max"] = Value::from(max);
+        }
+        Ok(())
+    }
+}
+
+fn read_u32(
+    bytes: &[u8],
+    offset: usize,
+) -> AppResult<u32> {
+    let bytes = bytes
+        .get(offset..offset + 4)
+        .context("Unexpected end of glb")?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::migration::{migrate_vrm0, Glb};
+    use serde_json::{json, Value};
+
+    fn vrm0_glb() -> Vec<u8> {
+        let bin = [1f32, 2., 3.]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        Glb {
+            json: json!({
+                "asset": { "version": "2.0" },
+                "nodes": [
+                    { "name": "hips", "translation": [1.0, 2.0, 3.0] },
+                    { "name": "face", "mesh": 0 }
+                ],
+                "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+                "accessors": [{
+                    "bufferView": 0,
+                    "componentType": 5126,
+                    "count": 1,
+                    "type": "VEC3",
+                    "min": [1.0, 2.0, 3.0],
+                    "max": [1.0, 2.0, 3.0]
+                }],
+                "bufferViews": [{ "buffer": 0, "byteLength": 12 }],
+                "buffers": [{ "byteLength": 12 }],
+                "extensions": {
+                    "VRM": {
+                        "humanoid": { "humanBones": [{ "bone": "hips", "node": 0 }] },
+                        "blendShapeMaster": {
+                            "blendShapeGroups": [{
+                                "name": "A",
+                                "presetName": "a",
+                                "binds": [{ "mesh": 0, "index": 0, "weight": 100 }]
+                            }]
+                        }
+                    }
+                }
+            }),
+            bin,
+        }
+        .to_bytes()
+        .unwrap()
+    }
+
+    #[test]
+    fn ignore_non_glb() -> TestResult {
+        assert!(migrate_vrm0(b"{}")?.is_none());
+        success!()
+    }
+
+    #[test]
+    fn reject_vrm0_json() {
+        assert!(migrate_vrm0(br#"{ "extensions": { "VRM": {} } }"#).is_err());
+        assert!(migrate_vrm0(br#"{ "extensions": { "VRMC_vrm": {} } }"#).is_ok());
+    }
+
+    #[test]
+    fn rotate_aliased_accessors_once() -> TestResult {
+        let mut glb = Glb::parse(&vrm0_glb())?.unwrap();
+        let accessor = glb.json["accessors"][0].clone();
+        glb.json["accessors"] = json!([accessor.clone(), accessor]);
+        glb.json["meshes"][0]["primitives"][0]["targets"] = json!([{ "POSITION": 1 }]);
+        let glb = Glb::parse(&migrate_vrm0(&glb.to_bytes()?)?.unwrap())?.unwrap();
+        let positions = glb
+            .bin
+            .chunks(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![-1., 2., -3.]);
+        assert_eq!(glb.json["accessors"][1]["min"], json!([-1.0, 2.0, -3.0]));
+        success!()
+    }
+
+    #[test]
+    fn rotate_node_translation() -> TestResult {
+        let glb = Glb::parse(&migrate_vrm0(&vrm0_glb())?.unwrap())?.unwrap();
+        assert_eq!(
+            glb.json["nodes"][0]["translation"],
+            json!([-1.0, 2.0, -3.0])
+        );
+        success!()
+    }
+
+    #[test]
+    fn rotate_positions() -> TestResult {
+        let glb = Glb::parse(&migrate_vrm0(&vrm0_glb())?.unwrap())?.unwrap();
+        let positions = glb
+            .bin
+            .chunks(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![-1., 2., -3.]);
+        assert_eq!(glb.json["accessors"][0]["min"], json!([-1.0, 2.0, -3.0]));
+        success!()
+    }
+
+    #[test]
+    fn insert_vrmc_vrm() -> TestResult {
+        let glb = Glb::parse(&migrate_vrm0(&vrm0_glb())?.unwrap())?.unwrap();
+        let vrmc_vrm = &glb.json["extensions"]["VRMC_vrm"];
+        assert_eq!(vrmc_vrm["humanoid"]["humanBones"]["hips"]["node"], 0);
+        assert_eq!(
+            vrmc_vrm["expressions"]["preset"]["aa"]["morphTargetBinds"][0]["node"],
+            1
+        );
+        assert!(glb.json["extensionsUsed"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("VRMC_vrm")));
+        success!()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::vrm::expressions::VrmExpressionRegistry;
+use crate::vrm::expressions::{VrmExpressionRegistry, VrmExpressionWeights};
 use crate::vrm::extensions::VrmExtensions;
 use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
 use crate::vrm::loader::{VrmAsset, VrmHandle};
@@ -50,7 +50,13 @@ fn spawn_vrm(
         cmd.insert((
             Vrm,
             SceneRoot(scene.clone()),
-            VrmExpressionRegistry::new(&extensions, &node_assets, &vrm.gltf.nodes),
+            VrmExpressionRegistry::new(
+                &extensions,
+                &node_assets,
+                &vrm.gltf.nodes,
+                &vrm.gltf.materials,
+            ),
+            VrmExpressionWeights::default(),
             HumanoidBoneRegistry::new(
                 &extensions.vrmc_vrm.humanoid.human_bones,
                 &node_assets,
@@ -66,7 +72,18 @@ fn spawn_vrm(
                     &node_assets,
                     &vrm.gltf.nodes,
                 ),
+                SpringJointLimitRegistry::new(
+                    &spring_bone.all_joints(),
+                    &node_assets,
+                    &vrm.gltf.nodes,
+                ),
+                SpringJointDefaultsReport::new(
+                    &spring_bone.all_joints(),
+                    &node_assets,
+                    &vrm.gltf.nodes,
+                ),
                 SpringColliderRegistry::new(&spring_bone.colliders, &node_assets, &vrm.gltf.nodes),
+                SpringColliderGroupRegistry::new(spring_bone, &node_assets, &vrm.gltf.nodes),
                 SpringNodeRegistry::new(spring_bone, &node_assets, &vrm.gltf.nodes),
             ));
         }
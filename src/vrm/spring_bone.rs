@@ -1,19 +1,38 @@
 mod attach;
+#[doc(hidden)]
+pub mod bench;
+pub mod cursor_collider;
+pub mod lod;
 pub mod registry;
+pub mod reset;
+pub mod snapshot;
+mod solver;
+pub mod switch;
+pub mod tuning;
 mod update;
+pub mod wind;
+pub mod world_collider;
 
 use crate::vrm::spring_bone::attach::SpringBoneAttachPlugin;
+use crate::vrm::spring_bone::lod::{SpringBoneLodPlugin, SpringChainLod};
 use crate::vrm::spring_bone::registry::SpringBoneRegistryPlugin;
+use crate::vrm::spring_bone::reset::SpringBoneResetPlugin;
+use crate::vrm::spring_bone::snapshot::SpringBoneSnapshotPlugin;
+use crate::vrm::spring_bone::switch::{SpringBoneSwitchPlugin, SpringChainActivity};
+use crate::vrm::spring_bone::tuning::SpringBoneTuningPlugin;
 use crate::vrm::spring_bone::update::SpringBoneUpdatePlugin;
+use crate::vrm::spring_bone::wind::SpringBoneWindPlugin;
+use crate::vrm::spring_bone::world_collider::SpringWorldColliderPlugin;
 use bevy::app::App;
 use bevy::math::{Mat4, Quat, Vec3};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// The component that holds the spring bone state of each Joint
 ///
 /// Implement the method described in the  [Official documentation](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_springBone-1.0/README.ja.md#%E5%88%9D%E6%9C%9F%E5%8C%96)
-#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
 #[reflect(Component, Serialize, Deserialize, Default)]
 pub struct SpringJointState {
     prev_tail: Vec3,
@@ -22,11 +41,50 @@ pub struct SpringJointState {
     bone_length: f32,
     initial_local_matrix: Mat4,
     initial_local_rotation: Quat,
+
+    /// The local rotation of the joint last set by other than the spring bone, such as animations.
+    ///
+    /// Disabled chains blend back to this rotation.
+    animated_rotation: Quat,
+}
+
+impl SpringJointState {
+    /// Creates the initial state of the joint.
+    ///
+    /// `tail_local_pos` is the position of the tail in the local space of the joint,
+    /// and `center` is the world matrix of the center node of the chain.
+    pub fn new(
+        joint_tf: &Transform,
+        joint_gtf: &GlobalTransform,
+        tail_local_pos: Vec3,
+        center: Mat4,
+    ) -> Self {
+        let tail_global_pos = joint_gtf.transform_point(tail_local_pos);
+        let tail = center.inverse().transform_point3(tail_global_pos);
+        Self {
+            prev_tail: tail,
+            current_tail: tail,
+            bone_axis: tail_local_pos.normalize(),
+            bone_length: tail_global_pos.distance(joint_gtf.translation()),
+            initial_local_matrix: joint_tf.compute_matrix(),
+            initial_local_rotation: joint_tf.rotation,
+            animated_rotation: joint_tf.rotation,
+        }
+    }
 }
 
 #[derive(Component, Reflect, Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Default)]
 #[reflect(Component, Serialize, Deserialize)]
+#[require(SpringChainActivity, SpringChainLod)]
 pub struct SpringRoot {
+    /// The name of the spring in `VRMC_springBone`.
+    ///
+    /// The name is not guaranteed to be unique, and may be empty.
+    pub name: String,
+
+    /// The entity of the VRM that the chain belongs to.
+    pub vrm: Option<Entity>,
+
     /// Represents a list of entity of spring joints belonging to the spring chain.
     /// This component is inserted into the root entity of the chain.
     pub joints: Vec<Entity>,
@@ -38,8 +96,8 @@ pub struct SpringRoot {
     pub center_node: Option<Entity>,
 }
 
-#[derive(Component, Reflect, Debug, Serialize, Deserialize, Copy, Clone, Default)]
-#[reflect(Component, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
 pub struct SpringJointProps {
     pub drag_force: f32,
     pub gravity_dir: Vec3,
@@ -48,6 +106,111 @@ pub struct SpringJointProps {
     pub stiffness: f32,
 }
 
+/// The default values are defined in the [`VRMC_springBone` specification](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_springBone-1.0/schema/VRMC_springBone.joint.schema.json).
+impl Default for SpringJointProps {
+    fn default() -> Self {
+        Self {
+            drag_force: 0.5,
+            gravity_dir: Vec3::NEG_Y,
+            gravity_power: 0.,
+            hit_radius: 0.,
+            stiffness: 1.,
+        }
+    }
+}
+
+/// The clock of the spring bone simulation.
+///
+/// The simulation advances in fixed steps of [`SpringBoneClock::timestep`] measured by [`Time<Virtual>`],
+/// so the result does not depend on the frame rate, and pausing or scaling the virtual time also applies to spring bones.
+#[derive(Resource, Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct SpringBoneClock {
+    /// The duration of a simulation step.
+    ///
+    /// [`Duration::ZERO`] stops the simulation.
+    pub timestep: Duration,
+
+    /// The maximum number of steps in a frame.
+    ///
+    /// The remaining time is discarded to prevent chains from exploding after a frame hitch.
+    /// `0` is treated as `1`; pause [`Time<Virtual>`] or the chains to stop the simulation.
+    pub max_substeps: u32,
+
+    /// If `true`, the rendered pose is interpolated between the last two steps by the remaining time.
+    pub interpolate: bool,
+
+    accumulated: Duration,
+    elapsed: Duration,
+}
+
+impl SpringBoneClock {
+    /// Creates a new clock that steps at `hz` times per second.
+    ///
+    /// If `hz` is not positive and finite, or its timestep cannot be represented by [`Duration`],
+    /// [`SpringBoneClock::timestep`] is set to [`Duration::ZERO`] and the clock never steps.
+    pub fn from_hz(hz: f64) -> Self {
+        let timestep = if hz.is_finite() && 0. < hz {
+            Duration::try_from_secs_f64(1. / hz).unwrap_or(Duration::ZERO)
+        } else {
+            Duration::ZERO
+        };
+        Self {
+            timestep,
+            ..default()
+        }
+    }
+
+    /// Accumulates `delta` and returns the number of steps to simulate.
+    pub fn advance(
+        &mut self,
+        delta: Duration,
+    ) -> u32 {
+        if self.timestep.is_zero() {
+            return 0;
+        }
+        self.accumulated += delta;
+        let mut steps = 0;
+        while self.timestep <= self.accumulated {
+            self.accumulated -= self.timestep;
+            self.elapsed += self.timestep;
+            steps += 1;
+            if self.max_substeps.max(1) <= steps {
+                self.accumulated = Duration::ZERO;
+                break;
+            }
+        }
+        steps
+    }
+
+    /// Returns the total simulated time.
+    #[inline]
+    pub const fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the ratio of the accumulated time that has not been simulated yet to [`SpringBoneClock::timestep`].
+    #[inline]
+    pub fn overstep_fraction(&self) -> f32 {
+        if self.timestep.is_zero() {
+            return 0.;
+        }
+        (self.accumulated.as_secs_f32() / self.timestep.as_secs_f32()).min(1.)
+    }
+}
+
+impl Default for SpringBoneClock {
+    fn default() -> Self {
+        Self {
+            timestep: Duration::from_secs_f64(1. / 60.),
+            max_substeps: 4,
+            interpolate: true,
+            accumulated: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
 pub struct VrmSpringBonePlugin;
 
 impl Plugin for VrmSpringBonePlugin {
@@ -55,10 +218,76 @@ impl Plugin for VrmSpringBonePlugin {
         &self,
         app: &mut App,
     ) {
-        app.add_plugins((
-            SpringBoneAttachPlugin,
-            SpringBoneRegistryPlugin,
-            SpringBoneUpdatePlugin,
-        ));
+        app.register_type::<SpringBoneClock>()
+            .init_resource::<SpringBoneClock>()
+            .add_plugins((
+                SpringBoneAttachPlugin,
+                SpringBoneRegistryPlugin,
+                SpringBoneUpdatePlugin,
+                SpringBoneResetPlugin,
+                SpringBoneSnapshotPlugin,
+                SpringBoneWindPlugin,
+                SpringBoneTuningPlugin,
+                SpringBoneSwitchPlugin,
+                SpringWorldColliderPlugin,
+                SpringBoneLodPlugin,
+            ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::spring_bone::SpringBoneClock;
+    use std::time::Duration;
+
+    #[test]
+    fn advance_fixed_steps() {
+        let mut clock = SpringBoneClock::from_hz(10.);
+        clock.max_substeps = 8;
+        assert_eq!(clock.advance(Duration::from_millis(250)), 2);
+        assert!((clock.overstep_fraction() - 0.5).abs() < 1e-5);
+        assert_eq!(clock.advance(Duration::from_millis(50)), 1);
+        assert!(clock.overstep_fraction() < 1e-5);
+        assert_eq!(clock.elapsed(), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn discard_time_over_max_substeps() {
+        let mut clock = SpringBoneClock::from_hz(10.);
+        clock.max_substeps = 2;
+        assert_eq!(clock.advance(Duration::from_secs(1)), 2);
+        assert_eq!(clock.advance(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn step_at_least_once_with_zero_max_substeps() {
+        let mut clock = SpringBoneClock::from_hz(10.);
+        clock.max_substeps = 0;
+        assert_eq!(clock.advance(Duration::from_millis(350)), 1);
+        assert!(clock.overstep_fraction() < 1e-5);
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn invalid_hz_never_steps() {
+        for hz in [
+            0.,
+            -30.,
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE,
+        ] {
+            let mut clock = SpringBoneClock::from_hz(hz);
+            assert_eq!(clock.timestep, Duration::ZERO);
+            assert_eq!(clock.advance(Duration::from_secs(1)), 0);
+            assert_eq!(clock.overstep_fraction(), 0.);
+        }
+    }
+
+    #[test]
+    fn paused_time_does_not_advance() {
+        let mut clock = SpringBoneClock::default();
+        assert_eq!(clock.advance(Duration::ZERO), 0);
     }
 }
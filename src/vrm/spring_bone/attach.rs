@@ -2,11 +2,11 @@ use crate::macros::marker_component;
 use crate::system_param::child_searcher::ChildSearcher;
 use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
 use crate::vrm::spring_bone::registry::{
-    SpringColliderRegistry, SpringJointPropsRegistry, SpringNodeRegistry,
+    collider_entity_name, SpringColliderRegistry, SpringJointLimitRegistry,
+    SpringJointPropsRegistry, SpringNodeRegistry,
 };
 use crate::vrm::spring_bone::{SpringJointState, SpringRoot};
 use bevy::app::{App, Update};
-use bevy::math::NormedVectorSpace;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -24,8 +24,8 @@ impl Plugin for SpringBoneAttachPlugin {
                 Update,
                 (
                     attach_joint_props,
-                    attach_collider_shapes,
-                    attach_spring_roots,
+                    // The spring roots find the collider entities spawned for the nodes with several colliders.
+                    (attach_collider_shapes, attach_spring_roots).chain(),
                     init_spring_joint_states,
                 ),
             );
@@ -57,13 +57,18 @@ fn attach_joint_props(
     par_commands: ParallelCommands,
     child_searcher: ChildSearcher,
     mascots: Query<
-        (Entity, &SpringJointPropsRegistry, &HumanoidBoneRegistry),
+        (
+            Entity,
+            &SpringJointPropsRegistry,
+            Option<&SpringJointLimitRegistry>,
+            &HumanoidBoneRegistry,
+        ),
         Without<AttachedJointProps>,
     >,
 ) {
     mascots
         .par_iter()
-        .for_each(|(entity, nodes, bone_registry)| {
+        .for_each(|(entity, nodes, limits, bone_registry)| {
             if !child_searcher.has_been_spawned_all_bones(entity, bone_registry) {
                 return;
             }
@@ -73,8 +78,13 @@ fn attach_joint_props(
                 else {
                     continue;
                 };
+                let limit = limits.and_then(|limits| limits.get(name)).copied();
                 par_commands.command_scope(|mut commands| {
-                    commands.entity(joint_entity).insert(*props);
+                    let mut joint = commands.entity(joint_entity);
+                    joint.insert(*props);
+                    if let Some(limit) = limit {
+                        joint.insert(limit);
+                    }
                 });
             }
             par_commands.command_scope(|mut commands| {
@@ -95,12 +105,24 @@ fn attach_collider_shapes(
         if !child_searcher.has_been_spawned_all_bones(entity, bone_registry) {
             return;
         }
-        for (name, shape) in nodes.iter() {
-            let Some(collider_entity) = child_searcher.find_from_name(entity, name) else {
+        for (name, shapes) in nodes.iter() {
+            let Some(node_entity) = child_searcher.find_from_name(entity, name) else {
                 continue;
             };
             par_commands.command_scope(|mut commands| {
-                commands.entity(collider_entity).insert(*shape);
+                for (nth, shape) in shapes.iter().enumerate() {
+                    if nth == 0 {
+                        commands.entity(node_entity).insert(*shape);
+                    } else {
+                        commands
+                            .spawn((
+                                collider_entity_name(name, nth),
+                                Transform::default(),
+                                *shape,
+                            ))
+                            .set_parent(node_entity);
+                    }
+                }
             });
         }
         par_commands.command_scope(|mut commands| {
@@ -125,6 +147,8 @@ fn attach_spring_roots(
             }
 
             for spring_root in registry.0.iter().map(|spring| SpringRoot {
+                name: spring.name.clone(),
+                vrm: Some(entity),
                 center_node: spring
                     .center
                     .as_ref()
@@ -154,38 +178,96 @@ fn attach_spring_roots(
         });
 }
 
+/// Initializes the joint states of the chains attached in this frame.
+///
+/// The global transforms have not been propagated yet for the entities spawned in this frame,
+/// so the initial pose is computed by composing the local transforms of the ancestors.
 fn init_spring_joint_states(
     par_commands: ParallelCommands,
-    spring_roots: Query<(Entity, &SpringRoot), Added<SpringRoot>>,
-    joints: Query<&Transform>,
+    spring_roots: Query<&SpringRoot, Added<SpringRoot>>,
+    transforms: Query<(&Transform, Option<&Parent>)>,
 ) {
-    spring_roots.par_iter().for_each(|(root_entity, root)| {
-        let mut parent = root_entity;
-        for joint_entity in root.joints.iter() {
-            let Ok(tf) = joints.get(*joint_entity) else {
+    spring_roots.par_iter().for_each(|root| {
+        let center = root
+            .center_node
+            .map(|center| composed_matrix(center, &transforms))
+            .unwrap_or(Mat4::IDENTITY);
+        for (i, joint_entity) in root.joints.iter().enumerate() {
+            let Ok((joint_tf, parent)) = transforms.get(*joint_entity) else {
                 continue;
             };
-            let state = SpringJointState {
-                prev_tail: tf.translation,
-                current_tail: tf.translation,
-                bone_axis: tf.translation.normalize(),
-                bone_length: tf.translation.norm(),
-                initial_local_matrix: tf.compute_matrix(),
-                initial_local_rotation: tf.rotation,
+            let joint_matrix = composed_matrix(*joint_entity, &transforms);
+            let tail_local_pos = match root.joints.get(i + 1) {
+                Some(tail_entity) => {
+                    let Ok((tail_tf, _)) = transforms.get(*tail_entity) else {
+                        continue;
+                    };
+                    tail_tf.translation
+                }
+                None => {
+                    let parent_matrix = parent
+                        .map(|parent| composed_matrix(parent.get(), &transforms))
+                        .unwrap_or(Mat4::IDENTITY);
+                    virtual_tail_local_pos(joint_matrix, parent_matrix)
+                }
             };
+            let joint_gtf = GlobalTransform::from(joint_matrix);
+            let state = SpringJointState::new(joint_tf, &joint_gtf, tail_local_pos, center);
+            let joint_entity = *joint_entity;
             par_commands.command_scope(|mut commands| {
-                commands.entity(parent).insert(state);
+                commands.entity(joint_entity).insert(state);
             });
-            parent = *joint_entity;
         }
     });
 }
 
+/// Returns the world matrix of the entity composed of the local transforms up to the first ancestor without [`Transform`].
+fn composed_matrix(
+    entity: Entity,
+    transforms: &Query<(&Transform, Option<&Parent>)>,
+) -> Mat4 {
+    let mut matrix = Mat4::IDENTITY;
+    let mut current = Some(entity);
+    while let Some((tf, parent)) = current.and_then(|entity| transforms.get(entity).ok()) {
+        matrix = tf.compute_matrix() * matrix;
+        current = parent.map(Parent::get);
+    }
+    matrix
+}
+
+/// The length of the virtual tail extrapolated for the last joint of a chain.
+///
+/// This is the same value as [`UniVRM`](https://github.com/vrm-c/UniVRM) and [`three-vrm`](https://github.com/pixiv/three-vrm).
+const VIRTUAL_TAIL_LENGTH: f32 = 0.07;
+
+/// Returns the local position of the virtual tail of the last joint in a chain.
+///
+/// The tail is placed [`VIRTUAL_TAIL_LENGTH`] away from the joint in world space, in the direction from the parent to the joint.
+/// If the joint sits at the origin of the parent, the local `-Y` axis of the joint is used instead.
+fn virtual_tail_local_pos(
+    joint_matrix: Mat4,
+    parent_matrix: Mat4,
+) -> Vec3 {
+    let joint_pos = joint_matrix.transform_point3(Vec3::ZERO);
+    let dir = (joint_pos - parent_matrix.transform_point3(Vec3::ZERO))
+        .try_normalize()
+        .or_else(|| joint_matrix.transform_vector3(Vec3::NEG_Y).try_normalize())
+        .unwrap_or(Vec3::NEG_Y);
+    let tail_local_pos = joint_matrix
+        .inverse()
+        .transform_point3(joint_pos + dir * VIRTUAL_TAIL_LENGTH);
+    if tail_local_pos.is_finite() && tail_local_pos != Vec3::ZERO {
+        tail_local_pos
+    } else {
+        Vec3::NEG_Y * VIRTUAL_TAIL_LENGTH
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::success;
     use crate::tests::{test_app, TestResult};
-    use crate::vrm::extensions::vrmc_spring_bone::ColliderShape;
+    use crate::vrm::extensions::vrmc_spring_bone::SpringColliderShape;
     use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
     use crate::vrm::spring_bone::attach::{
         attach_collider_shapes, attach_joint_props, attach_spring_roots, init_spring_joint_states,
@@ -198,28 +280,31 @@ mod tests {
     use bevy::app::App;
     use bevy::core::Name;
     use bevy::ecs::system::RunSystemOnce;
-    use bevy::math::Vec3;
-    use bevy::prelude::{BuildChildren, Commands, Entity, Transform};
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{BuildChildren, Commands, Entity, GlobalTransform, Transform};
     use bevy::utils::default;
+    use std::f32::consts::FRAC_PI_2;
 
     #[test]
     fn test_attach_spring_root() -> TestResult {
         let mut app = test_app();
-        let head: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
-            let head = commands.spawn(Name::new("head")).id();
-            commands
-                .spawn((
-                    SpringNodeRegistry(vec![SpringNode {
-                        center: None,
-                        joints: vec![Name::new("head")],
-                        ..default()
-                    }]),
-                    HumanoidBoneRegistry::default(),
-                ))
-                .with_child(Name::new("Root"))
-                .add_child(head);
-            head
-        })?;
+        let (vrm, head): (Entity, Entity) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                let head = commands.spawn(Name::new("head")).id();
+                let vrm = commands
+                    .spawn((
+                        SpringNodeRegistry(vec![SpringNode {
+                            center: None,
+                            joints: vec![Name::new("head")],
+                            ..default()
+                        }]),
+                        HumanoidBoneRegistry::default(),
+                    ))
+                    .with_child(Name::new("Root"))
+                    .add_child(head)
+                    .id();
+                (vrm, head)
+            })?;
         app.update();
 
         app.world_mut().run_system_once(attach_spring_roots)?;
@@ -236,6 +321,7 @@ mod tests {
             (
                 head,
                 &SpringRoot {
+                    vrm: Some(vrm),
                     joints: vec![head,],
                     ..default()
                 }
@@ -247,11 +333,11 @@ mod tests {
     #[test]
     fn set_center_node_spring_root() -> TestResult {
         let mut app = test_app();
-        let (center, head): (Entity, Entity) =
+        let (vrm, center, head): (Entity, Entity, Entity) =
             app.world_mut().run_system_once(|mut commands: Commands| {
                 let center = commands.spawn(Name::new("center")).id();
                 let head = commands.spawn(Name::new("head")).id();
-                commands
+                let vrm = commands
                     .spawn((
                         SpringNodeRegistry(vec![SpringNode {
                             center: Some(Name::new("center")),
@@ -262,8 +348,9 @@ mod tests {
                     ))
                     .with_child(Name::new("Root"))
                     .add_child(head)
-                    .add_child(center);
-                (center, head)
+                    .add_child(center)
+                    .id();
+                (vrm, center, head)
             })?;
         app.update();
 
@@ -281,6 +368,7 @@ mod tests {
             (
                 head,
                 &SpringRoot {
+                    vrm: Some(vrm),
                     center_node: Some(center),
                     joints: vec![head,],
                     ..default()
@@ -308,7 +396,11 @@ mod tests {
                 ))
                 .with_child(Name::new("Root"))
                 .add_child(head)
-                .with_child((Name::new("tail"), Transform::from_xyz(0.0, 2.0, 0.0)));
+                .with_child((
+                    Name::new("tail"),
+                    Transform::from_xyz(0.0, 2.0, 0.0),
+                    GlobalTransform::from_xyz(0.0, 2.0, 0.0),
+                ));
             head
         })?;
         app.update();
@@ -319,29 +411,215 @@ mod tests {
         app.world_mut().run_system_once(init_spring_joint_states)?;
         app.update();
 
-        let states = app
-            .world_mut()
-            .query::<(Entity, &SpringJointState)>()
-            .iter(app.world_mut())
-            .collect::<Vec<_>>();
-
         assert_eq!(
-            states,
-            vec![(
-                head,
-                &SpringJointState {
-                    current_tail: Vec3::new(0.0, 2.0, 0.0),
-                    prev_tail: Vec3::new(0.0, 2.0, 0.0),
-                    bone_axis: Vec3::new(0.0, 2.0, 0.0).normalize(),
-                    bone_length: 2.0,
-                    initial_local_matrix: Transform::from_xyz(0.0, 2.0, 0.0).compute_matrix(),
-                    ..default()
-                },
-            )]
+            app.world().get::<SpringJointState>(head),
+            Some(&SpringJointState {
+                current_tail: Vec3::new(0.0, 2.0, 0.0),
+                prev_tail: Vec3::new(0.0, 2.0, 0.0),
+                bone_axis: Vec3::new(0.0, 2.0, 0.0).normalize(),
+                bone_length: 2.0,
+                // The state is attached to the joint itself, so this is the transform of `head`, not of its tail.
+                initial_local_matrix: Transform::from_xyz(0.0, 0.0, 0.0).compute_matrix(),
+                ..default()
+            })
         );
         success!()
     }
 
+    #[test]
+    fn init_from_unpropagated_transforms() -> TestResult {
+        let mut app = test_app();
+        let head: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
+            let tail = commands
+                .spawn((Name::new("tail"), Transform::from_xyz(0.0, 0.5, 0.0)))
+                .id();
+            let head = commands
+                .spawn((Name::new("head"), Transform::from_xyz(0.0, 1.0, 0.0)))
+                .add_child(tail)
+                .id();
+            // The global transforms are left at the identity as if they had not been propagated yet.
+            commands
+                .spawn((
+                    SpringNodeRegistry(vec![SpringNode {
+                        center: None,
+                        joints: vec![Name::new("head"), Name::new("tail")],
+                        ..default()
+                    }]),
+                    HumanoidBoneRegistry::default(),
+                    Transform::from_xyz(1.0, 0.0, 0.0),
+                ))
+                .with_child(Name::new("Root"))
+                .add_child(head);
+            head
+        })?;
+        app.world_mut().run_system_once(attach_spring_roots)?;
+        app.world_mut().run_system_once(init_spring_joint_states)?;
+
+        let state = app.world().get::<SpringJointState>(head).unwrap();
+        assert!(state
+            .current_tail
+            .abs_diff_eq(Vec3::new(1.0, 1.5, 0.0), 1e-6));
+        assert!((state.bone_length - 0.5).abs() < 1e-6);
+        success!()
+    }
+
+    #[test]
+    fn init_virtual_tail_of_last_joint() -> TestResult {
+        let mut app = test_app();
+        let (head, tail): (Entity, Entity) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                let head = commands
+                    .spawn((Name::new("head"), Transform::default()))
+                    .id();
+                let tail = commands
+                    .spawn((
+                        Name::new("tail"),
+                        Transform::from_xyz(0.0, 2.0, 0.0),
+                        GlobalTransform::from_xyz(0.0, 2.0, 0.0),
+                    ))
+                    .id();
+                commands
+                    .spawn((
+                        SpringNodeRegistry(vec![SpringNode {
+                            center: None,
+                            joints: vec![Name::new("head"), Name::new("tail")],
+                            ..default()
+                        }]),
+                        HumanoidBoneRegistry::default(),
+                    ))
+                    .with_child(Name::new("Root"))
+                    .add_child(head)
+                    .add_child(tail);
+                (head, tail)
+            })?;
+        app.update();
+
+        app.world_mut().run_system_once(attach_spring_roots)?;
+        app.update();
+
+        app.world_mut().run_system_once(init_spring_joint_states)?;
+        app.update();
+
+        assert!(app.world().get::<SpringJointState>(head).is_some());
+        let state = app.world().get::<SpringJointState>(tail).unwrap();
+        assert_eq!(state.current_tail, Vec3::new(0.0, 2.07, 0.0));
+        assert_eq!(state.bone_axis, Vec3::Y);
+        assert!((state.bone_length - 0.07).abs() < 1e-6);
+        success!()
+    }
+
+    #[test]
+    fn init_single_joint_chain() -> TestResult {
+        let mut app = test_app();
+        let head: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
+            let head = commands
+                .spawn((
+                    Name::new("head"),
+                    Transform::from_xyz(1.0, 0.0, 0.0),
+                    GlobalTransform::from_xyz(1.0, 0.0, 0.0),
+                ))
+                .id();
+            commands
+                .spawn((
+                    SpringNodeRegistry(vec![SpringNode {
+                        center: None,
+                        joints: vec![Name::new("head")],
+                        ..default()
+                    }]),
+                    HumanoidBoneRegistry::default(),
+                ))
+                .with_child(Name::new("Root"))
+                .add_child(head);
+            head
+        })?;
+        app.update();
+
+        app.world_mut().run_system_once(attach_spring_roots)?;
+        app.update();
+
+        app.world_mut().run_system_once(init_spring_joint_states)?;
+        app.update();
+
+        let state = app.world().get::<SpringJointState>(head).unwrap();
+        assert_eq!(state.bone_axis, Vec3::X);
+        assert!((state.current_tail - Vec3::new(1.07, 0.0, 0.0)).length() < 1e-6);
+        success!()
+    }
+
+    #[test]
+    fn init_virtual_tail_of_rotated_last_joint() -> TestResult {
+        let mut app = test_app();
+        let head: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
+            let head = commands
+                .spawn((
+                    Name::new("head"),
+                    Transform::from_xyz(0.0, 1.0, 0.0)
+                        .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+                        .with_scale(Vec3::splat(2.0)),
+                ))
+                .id();
+            commands
+                .spawn((
+                    SpringNodeRegistry(vec![SpringNode {
+                        center: None,
+                        joints: vec![Name::new("head")],
+                        ..default()
+                    }]),
+                    HumanoidBoneRegistry::default(),
+                ))
+                .with_child(Name::new("Root"))
+                .add_child(head);
+            head
+        })?;
+        app.update();
+
+        app.world_mut().run_system_once(attach_spring_roots)?;
+        app.update();
+
+        app.world_mut().run_system_once(init_spring_joint_states)?;
+        app.update();
+
+        let state = app.world().get::<SpringJointState>(head).unwrap();
+        assert!((state.current_tail - Vec3::new(0.0, 1.07, 0.0)).length() < 1e-5);
+        assert!((state.bone_axis - Vec3::X).length() < 1e-5);
+        assert!((state.bone_length - 0.07).abs() < 1e-5);
+        success!()
+    }
+
+    #[test]
+    fn init_virtual_tail_of_joint_at_parent_origin() -> TestResult {
+        let mut app = test_app();
+        let head: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
+            let head = commands
+                .spawn((Name::new("head"), Transform::default()))
+                .id();
+            commands
+                .spawn((
+                    SpringNodeRegistry(vec![SpringNode {
+                        center: None,
+                        joints: vec![Name::new("head")],
+                        ..default()
+                    }]),
+                    HumanoidBoneRegistry::default(),
+                ))
+                .with_child(Name::new("Root"))
+                .add_child(head);
+            head
+        })?;
+        app.update();
+
+        app.world_mut().run_system_once(attach_spring_roots)?;
+        app.update();
+
+        app.world_mut().run_system_once(init_spring_joint_states)?;
+        app.update();
+
+        let state = app.world().get::<SpringJointState>(head).unwrap();
+        assert_eq!(state.bone_axis, Vec3::NEG_Y);
+        assert!((state.current_tail - Vec3::new(0.0, -0.07, 0.0)).length() < 1e-6);
+        success!()
+    }
+
     #[test]
     fn has_been_attached_joint_props() -> TestResult {
         let mut app = test_app();
@@ -372,6 +650,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn spawn_entities_for_extra_colliders() -> TestResult {
+        let mut app = test_app();
+        let (vrm, head) = app.world_mut().run_system_once(|mut commands: Commands| {
+            let head = commands.spawn(Name::new("head")).id();
+            let vrm = commands
+                .spawn((
+                    SpringColliderRegistry(
+                        [(
+                            Name::new("head"),
+                            vec![
+                                SpringColliderShape::default(),
+                                SpringColliderShape::default(),
+                            ],
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    SpringNodeRegistry(vec![SpringNode {
+                        joints: vec![Name::new("head")],
+                        colliders: vec![Name::new("head"), Name::new("head#collider1")],
+                        ..default()
+                    }]),
+                    HumanoidBoneRegistry::default(),
+                ))
+                .add_child(head)
+                .id();
+            (vrm, head)
+        })?;
+        app.world_mut().run_system_once(attach_collider_shapes)?;
+        app.world_mut().run_system_once(attach_spring_roots)?;
+
+        let extra = app
+            .world_mut()
+            .query::<(Entity, &Name, &SpringColliderShape)>()
+            .iter(app.world())
+            .find(|(_, name, _)| name.as_str() == "head#collider1")
+            .map(|(entity, ..)| entity)
+            .unwrap();
+        assert!(app.world().get::<SpringColliderShape>(head).is_some());
+        let spring_root = app.world().get::<SpringRoot>(head).unwrap();
+        assert_eq!(spring_root.vrm, Some(vrm));
+        assert_eq!(spring_root.colliders, vec![head, extra]);
+        Ok(())
+    }
+
     #[test]
     fn has_been_attached_spring_roots() -> TestResult {
         let mut app = test_app();
@@ -397,7 +721,7 @@ mod tests {
                         ..default()
                     }]),
                     SpringColliderRegistry(
-                        [(Name::new("head"), ColliderShape::default())]
+                        [(Name::new("head"), vec![SpringColliderShape::default()])]
                             .into_iter()
                             .collect(),
                     ),
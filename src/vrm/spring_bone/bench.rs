@@ -0,0 +1,125 @@
+//! The entry point of the benchmarks of the spring bone simulation.
+//!
+//! This is not a part of the public API, and may change without notice.
+
+use crate::vrm::extensions::vrmc_spring_bone::{Sphere, SpringColliderShape};
+use crate::vrm::spring_bone::solver::{
+    advance_chains, par_advance_chains, SpringChainLink, SpringChainSolver, SpringSolverCollider,
+    SpringSolverJoint,
+};
+use crate::vrm::spring_bone::{SpringJointProps, SpringJointState};
+use bevy::math::{Mat4, Vec3};
+use bevy::prelude::{GlobalTransform, Transform};
+
+/// The spring chains of a benchmark scene.
+///
+/// Two scenes are equal if their chains are in the same state.
+#[derive(Debug, Clone)]
+pub struct SpringChainBench {
+    solvers: Vec<SpringChainSolver>,
+    splittable: Vec<bool>,
+}
+
+impl SpringChainBench {
+    /// Creates `chains` chains of `joints_per_chain` joints side by side, each of which collides with 4 spheres.
+    ///
+    /// Every other chain is a branch hanging from the third joint of the previous chain, like VRM 0.x hair.
+    pub fn new(
+        chains: usize,
+        joints_per_chain: usize,
+    ) -> Self {
+        let props = SpringJointProps {
+            gravity_power: 0.5,
+            ..Default::default()
+        };
+        let solvers = (0..chains)
+            .map(|i| {
+                let root = GlobalTransform::from_xyz(i as f32, 0., 0.);
+                let mut parent = root;
+                let joints = (0..joints_per_chain)
+                    .map(|j| {
+                        let tf = Transform::from_xyz(0., -0.1, 0.);
+                        let gtf = parent.mul_transform(tf);
+                        let joint = SpringSolverJoint {
+                            state: SpringJointState::new(
+                                &tf,
+                                &gtf,
+                                Vec3::new(0., -0.1, 0.),
+                                Mat4::IDENTITY,
+                            ),
+                            props,
+                            transform: tf,
+                            parent: (j == 0).then_some(root),
+                            global: gtf,
+                            limit: None,
+                        };
+                        parent = gtf;
+                        joint
+                    })
+                    .collect();
+                let colliders = (0..4)
+                    .map(|c| SpringSolverCollider {
+                        shape: SpringColliderShape::Sphere(Sphere {
+                            offset: [0., 0., 0.],
+                            radius: 0.1,
+                        }),
+                        transform: GlobalTransform::from_xyz(i as f32 + 0.05, -0.1 * c as f32, 0.),
+                    })
+                    .collect();
+                let links = (i % 2 == 1)
+                    .then_some(SpringChainLink {
+                        joint: 0,
+                        chains_back: 1,
+                        parent_joint: 2.min(joints_per_chain.saturating_sub(1)),
+                    })
+                    .into_iter()
+                    .collect();
+                SpringChainSolver {
+                    joints,
+                    colliders,
+                    links,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        Self {
+            solvers,
+            splittable: Vec::new(),
+        }
+    }
+
+    /// Advances the chains on the current thread.
+    pub fn advance(
+        &mut self,
+        steps: u32,
+        delta_time: f32,
+        overstep_fraction: Option<f32>,
+    ) {
+        advance_chains(&mut self.solvers, steps, delta_time, overstep_fraction);
+    }
+
+    /// Advances the chains in parallel, as the spring bone system does.
+    pub fn par_advance(
+        &mut self,
+        steps: u32,
+        delta_time: f32,
+        overstep_fraction: Option<f32>,
+    ) {
+        par_advance_chains(
+            &mut self.solvers,
+            &mut self.splittable,
+            steps,
+            delta_time,
+            overstep_fraction,
+        );
+    }
+}
+
+impl PartialEq for SpringChainBench {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.solvers == other.solvers
+    }
+}
@@ -0,0 +1,249 @@
+//! Lets the mouse cursor touch spring bones.
+//!
+//! This is not included in [`VrmSpringBonePlugin`](crate::vrm::spring_bone::VrmSpringBonePlugin),
+//! so add [`SpringCursorColliderPlugin`] to enable it.
+
+use crate::macros::marker_component;
+use crate::system_param::cameras::Cameras;
+use crate::vrm::extensions::vrmc_spring_bone::{Sphere, SpringColliderShape};
+use crate::vrm::spring_bone::update::update_spring_bones;
+use crate::vrm::spring_bone::world_collider::{SpringCollider, SpringColliderTargets};
+use crate::vrm::spring_bone::SpringRoot;
+use bevy::app::{App, PostUpdate};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SpringCursorColliderPlugin;
+
+impl Plugin for SpringCursorColliderPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringCursorCollider>()
+            .register_type::<CursorSpringCollider>()
+            .init_resource::<SpringCursorCollider>()
+            .add_systems(PostUpdate, move_cursor_collider.before(update_spring_bones));
+    }
+}
+
+/// The settings of the sphere collider that follows the mouse cursor.
+///
+/// The cursor is projected onto the plane that faces the camera and passes through the root of the spring chain nearest to the cursor,
+/// so the collider touches the chain regardless of its distance from the camera or the height of the VRM.
+#[derive(Resource, Reflect, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct SpringCursorCollider {
+    /// The radius of the sphere in the world space.
+    pub radius: f32,
+
+    /// The VRMs that the cursor touches.
+    pub targets: SpringColliderTargets,
+
+    /// If not empty, only the springs of the listed names are affected.
+    pub springs: Vec<String>,
+}
+
+impl Default for SpringCursorCollider {
+    fn default() -> Self {
+        Self {
+            radius: 0.05,
+            targets: SpringColliderTargets::All,
+            springs: Vec::new(),
+        }
+    }
+}
+
+impl SpringCursorCollider {
+    /// Returns `true` if the cursor touches the spring chain.
+    fn affects(
+        &self,
+        spring_root: &SpringRoot,
+    ) -> bool {
+        self.targets.contains(spring_root.vrm)
+            && (self.springs.is_empty() || self.springs.contains(&spring_root.name))
+    }
+
+    fn collider(&self) -> SpringCollider {
+        SpringCollider {
+            shape: SpringColliderShape::Sphere(Sphere {
+                offset: [0., 0., 0.],
+                radius: self.radius,
+            }),
+            targets: self.targets.clone(),
+            springs: self.springs.clone(),
+        }
+    }
+}
+
+marker_component!(
+    /// A marker component attached to the collider that follows the mouse cursor.
+    ///
+    /// The collider is spawned while the cursor is over a window, and despawned when it leaves.
+    CursorSpringCollider
+);
+
+fn move_cursor_collider(
+    mut commands: Commands,
+    settings: Res<SpringCursorCollider>,
+    cameras: Cameras,
+    windows: Query<(Entity, &Window)>,
+    spring_roots: Query<(&SpringRoot, &GlobalTransform), Without<CursorSpringCollider>>,
+    mut cursor_colliders: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut GlobalTransform,
+            &mut SpringCollider,
+        ),
+        (With<CursorSpringCollider>, Without<Camera>),
+    >,
+) {
+    let cursor_pos = windows.iter().find_map(|(window_entity, window)| {
+        let viewport_pos = window.cursor_position()?;
+        spring_roots
+            .iter()
+            .filter(|(spring_root, _)| settings.affects(spring_root))
+            .filter_map(|(_, root_gtf)| {
+                let root_pos = root_gtf.translation();
+                let pos = cameras.to_world_pos_at_depth(window_entity, viewport_pos, root_pos)?;
+                Some((pos, pos.distance_squared(root_pos)))
+            })
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .map(|(pos, _)| pos)
+    });
+
+    match (cursor_pos, cursor_colliders.get_single_mut()) {
+        (Some(pos), Ok((_, mut tf, mut gtf, mut collider))) => {
+            tf.translation = pos;
+            // The global transform is updated immediately because the spring bones are simulated before the propagation.
+            *gtf = GlobalTransform::from(*tf);
+            *collider = settings.collider();
+        }
+        (Some(pos), Err(_)) => {
+            commands.spawn((
+                CursorSpringCollider,
+                Transform::from_translation(pos),
+                GlobalTransform::from_translation(pos),
+                settings.collider(),
+            ));
+        }
+        (None, Ok((entity, ..))) => {
+            commands.entity(entity).despawn();
+        }
+        (None, Err(_)) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::cursor_collider::{
+        move_cursor_collider, CursorSpringCollider, SpringCursorCollider,
+    };
+    use crate::vrm::spring_bone::world_collider::SpringCollider;
+    use crate::vrm::spring_bone::SpringRoot;
+    use bevy::app::App;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::{Vec2, Vec3};
+    use bevy::prelude::{
+        Camera, Commands, Entity, GlobalTransform, Projection, Transform, Window, With,
+    };
+    use bevy::render::camera::RenderTarget;
+    use bevy::render::view::RenderLayers;
+    use bevy::window::{PrimaryWindow, WindowRef};
+
+    /// Creates the app with a camera at `z = 5` looking at the origin and a spring chain whose root is at `root_pos`,
+    /// and returns the app and the window of the camera.
+    fn cursor_app(root_pos: Vec3) -> (App, Entity) {
+        let mut app = test_app();
+        app.init_resource::<SpringCursorCollider>();
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world());
+        app.world_mut().spawn((
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..Default::default()
+            },
+            Projection::default(),
+            GlobalTransform::from(Transform::from_xyz(0., 0., 5.)),
+            RenderLayers::default(),
+        ));
+        app.world_mut().spawn((
+            SpringRoot::default(),
+            GlobalTransform::from_translation(root_pos),
+        ));
+        // Computes the viewport of the camera.
+        app.update();
+        (app, window)
+    }
+
+    fn set_cursor(
+        app: &mut App,
+        window: Entity,
+        offset: Vec2,
+    ) {
+        let mut window = app.world_mut().get_mut::<Window>(window).unwrap();
+        let center = window.size() / 2.;
+        window.set_cursor_position(Some(center + offset));
+    }
+
+    fn cursor_collider(app: &mut App) -> Option<(Entity, Vec3, Vec3)> {
+        app.world_mut()
+            .query_filtered::<(Entity, &Transform, &GlobalTransform), With<CursorSpringCollider>>()
+            .get_single(app.world())
+            .ok()
+            .map(|(entity, tf, gtf)| (entity, tf.translation, gtf.translation()))
+    }
+
+    #[test]
+    fn spawn_at_depth_of_spring_root() -> TestResult {
+        let (mut app, window) = cursor_app(Vec3::new(0.2, 1.5, -1.));
+        set_cursor(&mut app, window, Vec2::ZERO);
+        app.world_mut().run_system_once(move_cursor_collider)?;
+
+        let (_, pos, global_pos) = cursor_collider(&mut app).unwrap();
+        assert!(pos.abs_diff_eq(Vec3::new(0., 0., -1.), 1e-4));
+        assert_eq!(pos, global_pos);
+        Ok(())
+    }
+
+    #[test]
+    fn move_with_cursor() -> TestResult {
+        let (mut app, window) = cursor_app(Vec3::ZERO);
+        set_cursor(&mut app, window, Vec2::ZERO);
+        app.world_mut().run_system_once(move_cursor_collider)?;
+        let (collider, ..) = cursor_collider(&mut app).unwrap();
+
+        set_cursor(&mut app, window, Vec2::new(100., 0.));
+        app.world_mut().run_system_once(move_cursor_collider)?;
+        let (moved, pos, global_pos) = cursor_collider(&mut app).unwrap();
+        assert_eq!(collider, moved);
+        assert!(0. < pos.x);
+        assert!(pos.y.abs() < 1e-4 && pos.z.abs() < 1e-4);
+        assert_eq!(pos, global_pos);
+        Ok(())
+    }
+
+    #[test]
+    fn despawn_when_cursor_leaves() -> TestResult {
+        let mut app = test_app();
+        app.init_resource::<SpringCursorCollider>();
+        let collider: Entity = app.world_mut().run_system_once(|mut commands: Commands| {
+            commands
+                .spawn((
+                    CursorSpringCollider,
+                    Transform::default(),
+                    GlobalTransform::default(),
+                    SpringCollider::default(),
+                ))
+                .id()
+        })?;
+        app.world_mut().run_system_once(move_cursor_collider)?;
+
+        assert!(app.world().get_entity(collider).is_err());
+        Ok(())
+    }
+}
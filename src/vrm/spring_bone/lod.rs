@@ -0,0 +1,354 @@
+//! Reduces the cost of spring chains that are off-screen, far from cameras, or motionless.
+
+use crate::system_param::cameras::Cameras;
+use crate::vrm::spring_bone::solver::SpringChainSolver;
+use crate::vrm::spring_bone::update::update_spring_bones;
+use crate::vrm::spring_bone::SpringRoot;
+use bevy::app::{App, PostUpdate};
+use bevy::math::Mat4;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub struct SpringBoneLodPlugin;
+
+impl Plugin for SpringBoneLodPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringBoneLod>()
+            .register_type::<SpringChainLod>()
+            .init_resource::<SpringBoneLod>()
+            .add_systems(PostUpdate, update_spring_lod.before(update_spring_bones));
+    }
+}
+
+/// The settings of the level of detail of spring bones.
+///
+/// The distance is evaluated at the root of each chain, and the visibility at the root and the joints of each chain with [`Cameras`].
+/// If there are no cameras, all chains are simulated at the full rate.
+#[derive(Resource, Reflect, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct SpringBoneLod {
+    /// If `true`, the chains outside the viewports of all cameras are not simulated.
+    ///
+    /// This also stops the chains while the window is minimised.
+    pub cull_offscreen: bool,
+
+    /// The margin in logical pixels added around the viewports before culling,
+    /// so that the tails swinging beyond the last joint stay simulated near the edges.
+    pub cull_margin: f32,
+
+    /// The distance from the nearest camera beyond which the chains are simulated at a reduced rate.
+    pub far_distance: f32,
+
+    /// The number of clock steps covered by a single step of far chains.
+    pub far_interval: u32,
+
+    /// The speed of the tails in meters per second below which the chain is regarded as motionless.
+    pub sleep_speed: f32,
+
+    /// The duration for which the chain must be motionless before falling asleep.
+    pub sleep_delay: Duration,
+}
+
+impl Default for SpringBoneLod {
+    fn default() -> Self {
+        Self {
+            cull_offscreen: true,
+            cull_margin: 50.,
+            far_distance: 10.,
+            far_interval: 2,
+            sleep_speed: 0.01,
+            sleep_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The level of detail of the spring chain.
+///
+/// This is attached to the root entity of the chain together with [`SpringRoot`].
+/// A sleeping chain wakes up when its joints are animated, its root moves, or its tails touch colliders.
+///
+/// A joint is regarded as animated when its [`Transform`] has been changed since the last update by other than the spring bone,
+/// such as animation clips or user code, so writing the transforms of the joints every frame keeps the chain awake.
+/// [`ResetSpringBones`](crate::vrm::spring_bone::reset::ResetSpringBones) and
+/// [`SpringBoneSnapshots::restore`](crate::vrm::spring_bone::snapshot::SpringBoneSnapshots::restore)
+/// write the transforms without being regarded as animations.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct SpringChainLod {
+    interval: u32,
+    pending_steps: u32,
+    culled: bool,
+    still: Duration,
+    anchor: Option<Mat4>,
+}
+
+impl Default for SpringChainLod {
+    fn default() -> Self {
+        Self {
+            interval: 1,
+            pending_steps: 0,
+            culled: false,
+            still: Duration::ZERO,
+            anchor: None,
+        }
+    }
+}
+
+impl SpringChainLod {
+    /// Returns the number of clock steps covered by a single step of the chain.
+    ///
+    /// `0` means the chain is culled.
+    #[inline]
+    pub const fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Returns `true` if the chain has stopped simulating because it is motionless.
+    #[inline]
+    pub const fn is_asleep(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Applies `matrix` to the pose at which the chain fell asleep.
+    ///
+    /// This is used to carry a sleeping chain over to a VRM placed at another position.
+    pub(crate) fn transform_anchor(
+        &mut self,
+        matrix: Mat4,
+    ) {
+        if let Some(anchor) = self.anchor.as_mut() {
+            *anchor = matrix * *anchor;
+        }
+    }
+
+    /// Accumulates the clock steps of the frame, and returns the multiplier of the delta time
+    /// if the chain is simulated in this frame.
+    ///
+    /// The second value is `true` if the chain resumes from culling, in which case its tails are stale.
+    pub(crate) fn schedule(
+        &mut self,
+        steps: u32,
+    ) -> Option<(f32, bool)> {
+        if self.interval == 0 {
+            self.pending_steps = 0;
+            self.culled = true;
+            return None;
+        }
+        let resumed = std::mem::take(&mut self.culled);
+        if self.interval == 1 {
+            self.pending_steps = 0;
+            return Some((1., resumed));
+        }
+        self.pending_steps += steps;
+        if steps == 0 || self.pending_steps < self.interval {
+            self.culled = resumed;
+            return None;
+        }
+        let time_scale = self.pending_steps as f32 / steps as f32;
+        self.pending_steps = 0;
+        Some((time_scale, resumed))
+    }
+
+    /// Wakes the chain up if the chain gathered into `solver` has been disturbed.
+    ///
+    /// Returns `true` if the chain is awake.
+    pub(crate) fn wake(
+        &mut self,
+        solver: &SpringChainSolver,
+        animated: bool,
+    ) -> bool {
+        let Some(anchor) = self.anchor else {
+            return true;
+        };
+        if animated
+            || !anchor.abs_diff_eq(chain_anchor(solver), ANCHOR_TOLERANCE)
+            || solver.is_touching_colliders()
+        {
+            self.anchor = None;
+            self.still = Duration::ZERO;
+        }
+        !self.is_asleep()
+    }
+
+    /// Puts the chain to sleep if its tails have been slower than [`SpringBoneLod::sleep_speed`]
+    /// for [`SpringBoneLod::sleep_delay`].
+    pub(crate) fn settle(
+        &mut self,
+        solver: &SpringChainSolver,
+        speed: f32,
+        delta: Duration,
+        settings: &SpringBoneLod,
+    ) {
+        if settings.sleep_speed <= speed || solver.weight < 1. {
+            self.still = Duration::ZERO;
+            return;
+        }
+        self.still += delta;
+        if settings.sleep_delay <= self.still {
+            self.anchor = Some(chain_anchor(solver));
+        }
+    }
+}
+
+/// The tolerance of the root motion regarded as the chain being still.
+const ANCHOR_TOLERANCE: f32 = 1e-4;
+
+fn chain_anchor(solver: &SpringChainSolver) -> Mat4 {
+    solver
+        .joints
+        .first()
+        .and_then(|joint| joint.parent)
+        .map(|parent| parent.compute_matrix())
+        .unwrap_or(solver.center)
+}
+
+fn update_spring_lod(
+    settings: Res<SpringBoneLod>,
+    cameras: Cameras,
+    mut spring_roots: Query<(&SpringRoot, &GlobalTransform, &mut SpringChainLod)>,
+    joints: Query<&GlobalTransform>,
+) {
+    if cameras.cameras.is_empty() {
+        for (_, _, mut lod) in spring_roots.iter_mut() {
+            lod.interval = 1;
+        }
+        return;
+    }
+    for (spring_root, root_gtf, mut lod) in spring_roots.iter_mut() {
+        let pos = root_gtf.translation();
+        let visible = || {
+            cameras.is_in_viewport(pos, settings.cull_margin)
+                || spring_root
+                    .joints
+                    .iter()
+                    .filter_map(|joint| joints.get(*joint).ok())
+                    .any(|joint_gtf| {
+                        cameras.is_in_viewport(joint_gtf.translation(), settings.cull_margin)
+                    })
+        };
+        let interval = if settings.cull_offscreen && !visible() {
+            0
+        } else {
+            let distance = cameras
+                .cameras
+                .iter()
+                .map(|(_, camera_gtf, _)| camera_gtf.translation().distance(pos))
+                .fold(f32::INFINITY, f32::min);
+            if settings.far_distance < distance {
+                settings.far_interval.max(1)
+            } else {
+                1
+            }
+        };
+        lod.interval = interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::lod::{update_spring_lod, SpringBoneLod, SpringChainLod};
+    use crate::vrm::spring_bone::solver::SpringChainSolver;
+    use crate::vrm::spring_bone::SpringRoot;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::Vec3;
+    use bevy::prelude::{Camera, Entity, GlobalTransform, Projection, Transform, With};
+    use bevy::render::camera::RenderTarget;
+    use bevy::render::view::RenderLayers;
+    use bevy::utils::default;
+    use bevy::window::{PrimaryWindow, WindowRef};
+    use std::time::Duration;
+
+    #[test]
+    fn cull_and_slow_down_by_camera() -> TestResult {
+        let mut app = test_app();
+        app.init_resource::<SpringBoneLod>();
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world());
+        // The camera sees about 2 meters above and below its axis at the origin.
+        app.world_mut().spawn((
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..default()
+            },
+            Projection::default(),
+            GlobalTransform::from(Transform::from_xyz(0., 0., 5.)),
+            RenderLayers::default(),
+        ));
+        // Computes the viewport of the camera.
+        app.update();
+
+        let mut spawn_chain = |root_pos: Vec3, joint_pos: Vec3| {
+            let joint = app
+                .world_mut()
+                .spawn(GlobalTransform::from_translation(joint_pos))
+                .id();
+            app.world_mut()
+                .spawn((
+                    SpringRoot {
+                        joints: vec![joint],
+                        ..default()
+                    },
+                    GlobalTransform::from_translation(root_pos),
+                ))
+                .id()
+        };
+        let near = spawn_chain(Vec3::ZERO, Vec3::ZERO);
+        let far = spawn_chain(Vec3::new(0., 0., -20.), Vec3::new(0., 0., -20.));
+        let culled = spawn_chain(Vec3::new(0., 3., 0.), Vec3::new(0., 4., 0.));
+        let hanging = spawn_chain(Vec3::new(0., 3., 0.), Vec3::new(0., 1.5, 0.));
+        app.world_mut().run_system_once(update_spring_lod)?;
+
+        let interval = |chain| app.world().get::<SpringChainLod>(chain).unwrap().interval();
+        assert_eq!(interval(near), 1);
+        assert_eq!(interval(far), SpringBoneLod::default().far_interval);
+        assert_eq!(interval(culled), 0);
+        assert_eq!(interval(hanging), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn step_far_chains_less_often() {
+        let mut lod = SpringChainLod {
+            interval: 3,
+            ..default()
+        };
+        assert_eq!(lod.schedule(1), None);
+        assert_eq!(lod.schedule(1), None);
+        assert_eq!(lod.schedule(1), Some((3., false)));
+        assert_eq!(lod.schedule(0), None);
+    }
+
+    #[test]
+    fn resume_from_culling() {
+        let mut lod = SpringChainLod {
+            interval: 0,
+            ..default()
+        };
+        assert_eq!(lod.schedule(1), None);
+        lod.interval = 1;
+        assert_eq!(lod.schedule(1), Some((1., true)));
+        assert_eq!(lod.schedule(1), Some((1., false)));
+    }
+
+    #[test]
+    fn sleep_after_delay_and_wake_by_animation() {
+        let settings = SpringBoneLod::default();
+        let solver = SpringChainSolver::default();
+        let mut lod = SpringChainLod::default();
+        lod.settle(&solver, 0., Duration::from_millis(600), &settings);
+        assert!(!lod.is_asleep());
+        lod.settle(&solver, 0., Duration::from_millis(600), &settings);
+        assert!(lod.is_asleep());
+
+        assert!(!lod.wake(&solver, false));
+        assert!(lod.wake(&solver, true));
+        assert!(!lod.is_asleep());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::vrm::extensions::vrmc_spring_bone::{
-    Collider, ColliderShape, Spring, SpringJoint, VRMCSpringBone,
+    Collider, Spring, SpringColliderShape, SpringJoint, SpringJointLimit, VRMCSpringBone,
 };
 use crate::vrm::spring_bone::SpringJointProps;
 use bevy::app::App;
@@ -9,6 +9,7 @@ use bevy::gltf::GltfNode;
 use bevy::math::Vec3;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 
 pub struct SpringBoneRegistryPlugin;
 
@@ -19,37 +20,129 @@ impl Plugin for SpringBoneRegistryPlugin {
     ) {
         app.register_type::<SpringColliderRegistry>()
             .register_type::<SpringJointPropsRegistry>()
-            .register_type::<SpringNodeRegistry>();
+            .register_type::<SpringJointLimitRegistry>()
+            .register_type::<SpringJointDefaultsReport>()
+            .register_type::<SpringJointProperty>()
+            .register_type::<SpringNodeRegistry>()
+            .register_type::<SpringColliderGroupRegistry>();
     }
 }
 
+/// The shapes of the colliders keyed by the name of their node, in the order of the colliders.
+///
+/// A node may have several colliders, such as the spheres of a VRM 0.x collider group.
+/// The first collider is attached to the node itself,
+/// and the others are attached to the child entities named by [`collider_entity_name`].
 #[derive(Component, Deref, Reflect, PartialEq, Clone)]
 #[reflect(Component)]
-pub struct SpringColliderRegistry(pub(crate) HashMap<Name, ColliderShape>);
+pub struct SpringColliderRegistry(pub(crate) HashMap<Name, Vec<SpringColliderShape>>);
 
 impl SpringColliderRegistry {
     pub fn new(
         colliders: &[Collider],
         node_assets: &Assets<GltfNode>,
         nodes: &[Handle<GltfNode>],
+    ) -> Self {
+        let mut shapes = HashMap::<Name, Vec<SpringColliderShape>>::default();
+        for collider in colliders.iter() {
+            let Some(name) = get_node_name(collider.node, node_assets, nodes) else {
+                continue;
+            };
+            shapes
+                .entry(name)
+                .or_default()
+                .push(collider.resolved_shape());
+        }
+        Self(shapes)
+    }
+}
+
+/// Returns the name of the entity of the `nth` collider on the node.
+pub fn collider_entity_name(
+    node: &Name,
+    nth: usize,
+) -> Name {
+    if nth == 0 {
+        node.clone()
+    } else {
+        Name::new(format!("{node}#collider{nth}"))
+    }
+}
+
+/// Returns the names of the collider entities in the order of `colliders`.
+fn collider_entity_names(
+    colliders: &[Collider],
+    node_assets: &Assets<GltfNode>,
+    nodes: &[Handle<GltfNode>],
+) -> Vec<Option<Name>> {
+    let mut counts = HashMap::<usize, usize>::default();
+    colliders
+        .iter()
+        .map(|collider| {
+            let node = get_node_name(collider.node, node_assets, nodes)?;
+            let nth = counts.entry(collider.node).or_default();
+            let name = collider_entity_name(&node, *nth);
+            *nth += 1;
+            Some(name)
+        })
+        .collect()
+}
+
+/// The names of the collider nodes keyed by the name of the collider group.
+///
+/// Groups sharing the same name are merged.
+#[derive(Component, Deref, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct SpringColliderGroupRegistry(pub(crate) HashMap<String, Vec<Name>>);
+
+impl SpringColliderGroupRegistry {
+    pub fn new(
+        spring_bone: &VRMCSpringBone,
+        node_assets: &Assets<GltfNode>,
+        nodes: &[Handle<GltfNode>],
+    ) -> Self {
+        let names = collider_entity_names(&spring_bone.colliders, node_assets, nodes);
+        let mut groups = HashMap::<String, Vec<Name>>::default();
+        for group in spring_bone.collider_groups.iter() {
+            groups.entry(group.name.clone()).or_default().extend(
+                group
+                    .colliders
+                    .iter()
+                    .filter_map(|index| names.get(*index as usize).cloned().flatten()),
+            );
+        }
+        Self(groups)
+    }
+}
+
+#[derive(Component, Deref, Reflect)]
+pub struct SpringJointPropsRegistry(pub(crate) HashMap<Name, SpringJointProps>);
+
+impl SpringJointPropsRegistry {
+    /// The omitted properties are filled with the default values of the specification.
+    pub fn new(
+        joints: &[SpringJoint],
+        node_assets: &Assets<GltfNode>,
+        nodes: &[Handle<GltfNode>],
     ) -> Self {
         Self(
-            colliders
+            joints
                 .iter()
-                .filter_map(|collider| {
-                    let node_handle = nodes.get(collider.node)?;
-                    let node = node_assets.get(node_handle)?;
-                    Some((Name::new(node.name.clone()), collider.shape))
+                .filter_map(|joint| {
+                    let name = get_node_name(joint.node, node_assets, nodes)?;
+                    Some((name, joint_props(joint)))
                 })
                 .collect(),
         )
     }
 }
 
-#[derive(Component, Deref, Reflect)]
-pub struct SpringJointPropsRegistry(pub(crate) HashMap<Name, SpringJointProps>);
+/// The limits of the joints defined in `VRMC_springBone_limit`, keyed by the name of the joint node.
+#[derive(Component, Deref, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct SpringJointLimitRegistry(pub(crate) HashMap<Name, SpringJointLimit>);
 
-impl SpringJointPropsRegistry {
+impl SpringJointLimitRegistry {
     pub fn new(
         joints: &[SpringJoint],
         node_assets: &Assets<GltfNode>,
@@ -59,27 +152,89 @@ impl SpringJointPropsRegistry {
             joints
                 .iter()
                 .filter_map(|joint| {
-                    let node_handle = nodes.get(joint.node)?;
-                    let node = node_assets.get(node_handle)?;
-                    let dir = joint.gravity_dir?;
-                    Some((
-                        Name::new(node.name.clone()),
-                        SpringJointProps {
-                            drag_force: joint.drag_force?,
-                            gravity_power: joint.gravity_power?,
-                            hit_radius: joint.hit_radius?,
-                            stiffness: joint.stiffness?,
-                            gravity_dir: Vec3::new(dir[0], dir[1], dir[2]),
-                        },
-                    ))
+                    let limit = joint.extensions?.limit?.limit;
+                    let name = get_node_name(joint.node, node_assets, nodes)?;
+                    Some((name, limit))
                 })
                 .collect(),
         )
     }
 }
 
+/// The property of [`SpringJointProps`].
+#[derive(Reflect, Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum SpringJointProperty {
+    DragForce,
+    GravityDir,
+    GravityPower,
+    HitRadius,
+    Stiffness,
+}
+
+/// The report of the joints whose properties are omitted in the VRM and filled with the default values.
+///
+/// This is attached to the VRM entity.
+#[derive(Component, Deref, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct SpringJointDefaultsReport(pub(crate) HashMap<Name, Vec<SpringJointProperty>>);
+
+impl SpringJointDefaultsReport {
+    pub fn new(
+        joints: &[SpringJoint],
+        node_assets: &Assets<GltfNode>,
+        nodes: &[Handle<GltfNode>],
+    ) -> Self {
+        Self(
+            joints
+                .iter()
+                .filter_map(|joint| {
+                    let omitted = omitted_properties(joint);
+                    if omitted.is_empty() {
+                        return None;
+                    }
+                    let name = get_node_name(joint.node, node_assets, nodes)?;
+                    Some((name, omitted))
+                })
+                .collect(),
+        )
+    }
+}
+
+fn joint_props(joint: &SpringJoint) -> SpringJointProps {
+    let default = SpringJointProps::default();
+    SpringJointProps {
+        drag_force: joint.drag_force.unwrap_or(default.drag_force),
+        gravity_dir: joint
+            .gravity_dir
+            .map(Vec3::from)
+            .unwrap_or(default.gravity_dir),
+        gravity_power: joint.gravity_power.unwrap_or(default.gravity_power),
+        hit_radius: joint.hit_radius.unwrap_or(default.hit_radius),
+        stiffness: joint.stiffness.unwrap_or(default.stiffness),
+    }
+}
+
+fn omitted_properties(joint: &SpringJoint) -> Vec<SpringJointProperty> {
+    [
+        (joint.drag_force.is_none(), SpringJointProperty::DragForce),
+        (joint.gravity_dir.is_none(), SpringJointProperty::GravityDir),
+        (
+            joint.gravity_power.is_none(),
+            SpringJointProperty::GravityPower,
+        ),
+        (joint.hit_radius.is_none(), SpringJointProperty::HitRadius),
+        (joint.stiffness.is_none(), SpringJointProperty::Stiffness),
+    ]
+    .into_iter()
+    .filter_map(|(omitted, property)| omitted.then_some(property))
+    .collect()
+}
+
 #[derive(Component, Reflect, Debug, Default)]
 pub struct SpringNode {
+    /// The name of the spring in `VRMC_springBone`.
+    pub name: String,
     pub center: Option<Name>,
     pub joints: Vec<Name>,
     pub colliders: Vec<Name>,
@@ -94,17 +249,19 @@ impl SpringNodeRegistry {
         node_assets: &Assets<GltfNode>,
         nodes: &[Handle<GltfNode>],
     ) -> Self {
+        let collider_names = collider_entity_names(&spring_bone.colliders, node_assets, nodes);
         Self(
             spring_bone
                 .springs
                 .iter()
                 .map(|spring| SpringNode {
+                    name: spring.name.clone(),
                     joints: spring
                         .joints
                         .iter()
                         .filter_map(|joint| get_node_name(joint.node, node_assets, nodes))
                         .collect(),
-                    colliders: collider_names(spring_bone, spring, node_assets, nodes),
+                    colliders: spring_collider_names(spring_bone, spring, &collider_names),
                     center: spring
                         .center
                         .and_then(|index| get_node_name(index, node_assets, nodes)),
@@ -114,19 +271,19 @@ impl SpringNodeRegistry {
     }
 }
 
-fn collider_names(
+fn spring_collider_names(
     spring_bone: &VRMCSpringBone,
     spring: &Spring,
-    node_assets: &Assets<GltfNode>,
-    nodes: &[Handle<GltfNode>],
+    collider_names: &[Option<Name>],
 ) -> Vec<Name> {
     let Some(collider_groups) = spring.collider_groups.as_ref() else {
         return vec![];
     };
-    spring_bone
-        .spring_colliders(collider_groups)
+    collider_groups
         .iter()
-        .flat_map(|collider| get_node_name(collider.node, node_assets, nodes))
+        .filter_map(|group| spring_bone.collider_groups.get(*group))
+        .flat_map(|group| group.colliders.iter())
+        .filter_map(|index| collider_names.get(*index as usize).cloned().flatten())
         .collect()
 }
 
@@ -139,3 +296,110 @@ fn get_node_name(
     let node = node_assets.get(node_handle)?;
     Some(Name::new(node.name.clone()))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::TestResult;
+    use crate::vrm::extensions::vrmc_spring_bone::{SpringJoint, VRMCSpringBone};
+    use crate::vrm::spring_bone::registry::{
+        joint_props, omitted_properties, SpringColliderGroupRegistry, SpringColliderRegistry,
+        SpringJointProperty, SpringNodeRegistry,
+    };
+    use crate::vrm::spring_bone::SpringJointProps;
+    use bevy::asset::Assets;
+    use bevy::core::Name;
+    use bevy::gltf::GltfNode;
+    use bevy::math::Vec3;
+    use bevy::prelude::Transform;
+
+    #[test]
+    fn register_every_collider_on_same_node() -> TestResult {
+        // The spheres of a VRM 0.x collider group are converted into colliders on the same node.
+        let spring_bone: VRMCSpringBone = serde_json::from_str(
+            r#"{
+                "specVersion": "1.0",
+                "colliders": [
+                    { "node": 0, "shape": { "sphere": { "offset": [0, 0, 0], "radius": 0.1 } } },
+                    { "node": 0, "shape": { "sphere": { "offset": [0, 0.1, 0], "radius": 0.2 } } }
+                ],
+                "colliderGroups": [{ "name": "head", "colliders": [0, 1] }],
+                "springs": [{ "name": "hair", "joints": [], "colliderGroups": [0] }]
+            }"#,
+        )?;
+        let mut node_assets = Assets::<GltfNode>::default();
+        let nodes = vec![node_assets.add(GltfNode {
+            index: 0,
+            name: "Head".to_string(),
+            children: Vec::new(),
+            mesh: None,
+            skin: None,
+            transform: Transform::default(),
+            is_animation_root: false,
+            extras: None,
+        })];
+
+        let colliders = SpringColliderRegistry::new(&spring_bone.colliders, &node_assets, &nodes);
+        assert_eq!(colliders[&Name::new("Head")].len(), 2);
+        let expected = vec![Name::new("Head"), Name::new("Head#collider1")];
+        let groups = SpringColliderGroupRegistry::new(&spring_bone, &node_assets, &nodes);
+        assert_eq!(groups["head"], expected);
+        let springs = SpringNodeRegistry::new(&spring_bone, &node_assets, &nodes);
+        assert_eq!(springs[0].colliders, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn fill_omitted_props_with_defaults() {
+        let joint = SpringJoint {
+            node: 0,
+            drag_force: None,
+            gravity_dir: None,
+            gravity_power: Some(0.2),
+            hit_radius: None,
+            stiffness: Some(2.),
+            extensions: None,
+        };
+        assert_eq!(
+            joint_props(&joint),
+            SpringJointProps {
+                drag_force: 0.5,
+                gravity_dir: Vec3::NEG_Y,
+                gravity_power: 0.2,
+                hit_radius: 0.,
+                stiffness: 2.,
+            }
+        );
+        assert_eq!(
+            omitted_properties(&joint),
+            vec![
+                SpringJointProperty::DragForce,
+                SpringJointProperty::GravityDir,
+                SpringJointProperty::HitRadius,
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_fully_specified_props() {
+        let joint = SpringJoint {
+            node: 0,
+            drag_force: Some(0.1),
+            gravity_dir: Some([1., 0., 0.]),
+            gravity_power: Some(0.3),
+            hit_radius: Some(0.02),
+            stiffness: Some(0.7),
+            extensions: None,
+        };
+        assert_eq!(
+            joint_props(&joint),
+            SpringJointProps {
+                drag_force: 0.1,
+                gravity_dir: Vec3::X,
+                gravity_power: 0.3,
+                hit_radius: 0.02,
+                stiffness: 0.7,
+            }
+        );
+        assert!(omitted_properties(&joint).is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use crate::vrm::spring_bone::update::{center_matrix, update_spring_bones};
+use crate::vrm::spring_bone::{SpringJointState, SpringRoot};
+use bevy::app::{App, PostUpdate};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub struct SpringBoneResetPlugin;
+
+impl Plugin for SpringBoneResetPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<ResetSpringBones>()
+            .register_type::<SpringBoneAutoReset>()
+            .add_observer(observe_reset_spring_bones)
+            .add_systems(PostUpdate, detect_teleport.before(update_spring_bones));
+    }
+}
+
+/// The trigger event to reset the spring bones of the VRM.
+///
+/// The joints return to their animated rotations and lose their velocity.
+/// This is useful after teleporting or re-posing the VRM.
+#[derive(Event, Debug, Reflect, Copy, Clone)]
+pub struct ResetSpringBones;
+
+/// If this component is attached to the VRM entity, [`ResetSpringBones`] is triggered automatically
+/// when the VRM or the center of any spring chain moves more than [`SpringBoneAutoReset::threshold`] in one frame.
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct SpringBoneAutoReset {
+    /// The distance in the world space regarded as a teleport.
+    pub threshold: f32,
+    previous_positions: HashMap<Entity, Vec3>,
+}
+
+impl SpringBoneAutoReset {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            previous_positions: HashMap::default(),
+        }
+    }
+}
+
+fn detect_teleport(
+    mut commands: Commands,
+    mut vrms: Query<(Entity, &GlobalTransform, &mut SpringBoneAutoReset)>,
+    children: Query<&Children>,
+    spring_roots: Query<&SpringRoot>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for (vrm_entity, vrm_gtf, mut auto_reset) in vrms.iter_mut() {
+        let centers = children
+            .iter_descendants(vrm_entity)
+            .filter_map(|entity| spring_roots.get(entity).ok()?.center_node)
+            .filter_map(|center| Some((center, transforms.get(center).ok()?.translation())));
+        let positions = std::iter::once((vrm_entity, vrm_gtf.translation()))
+            .chain(centers)
+            .collect::<HashMap<_, _>>();
+        let teleported = positions.iter().any(|(entity, position)| {
+            auto_reset
+                .previous_positions
+                .get(entity)
+                .is_some_and(|previous| auto_reset.threshold < previous.distance(*position))
+        });
+        auto_reset.previous_positions = positions;
+        if teleported {
+            commands.entity(vrm_entity).trigger(ResetSpringBones);
+        }
+    }
+}
+
+fn observe_reset_spring_bones(
+    trigger: Trigger<ResetSpringBones>,
+    children: Query<&Children>,
+    spring_roots: Query<&SpringRoot>,
+    mut transforms: Query<(&mut Transform, &mut GlobalTransform)>,
+    mut joints: Query<(&Parent, &mut SpringJointState)>,
+) {
+    for spring_root in children
+        .iter_descendants(trigger.entity())
+        .filter_map(|entity| spring_roots.get(entity).ok())
+    {
+        reset_spring_chain(spring_root, &mut transforms, &mut joints);
+    }
+}
+
+fn reset_spring_chain(
+    spring_root: &SpringRoot,
+    transforms: &mut Query<(&mut Transform, &mut GlobalTransform)>,
+    joints: &mut Query<(&Parent, &mut SpringJointState)>,
+) {
+    let center_inverse = center_matrix(spring_root, transforms).inverse();
+    for joint in spring_root.joints.iter().copied() {
+        let Ok((parent, mut state)) = joints.get_mut(joint) else {
+            continue;
+        };
+        let parent_gtf = transforms
+            .get(parent.get())
+            .map(|(_, gtf)| *gtf)
+            .unwrap_or_default();
+        let Ok((mut tf, mut gtf)) = transforms.get_mut(joint) else {
+            continue;
+        };
+        // Like `SpringChainSolver::reset_tails`, the joints return to the pose set by animations.
+        // The change is not detected so that the reset is not regarded as an animation waking sleeping chains.
+        let tf = tf.bypass_change_detection();
+        tf.rotation = state.animated_rotation;
+        let gtf = gtf.bypass_change_detection();
+        *gtf = parent_gtf.mul_transform(*tf);
+
+        let tail = gtf.translation() + gtf.rotation() * state.bone_axis * state.bone_length;
+        let tail = center_inverse.transform_point3(tail);
+        state.prev_tail = tail;
+        state.current_tail = tail;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::lod::{SpringBoneLod, SpringChainLod};
+    use crate::vrm::spring_bone::reset::{
+        detect_teleport, observe_reset_spring_bones, ResetSpringBones, SpringBoneAutoReset,
+    };
+    use crate::vrm::spring_bone::solver::SpringChainSolver;
+    use crate::vrm::spring_bone::update::tests::{spring_app, step};
+    use crate::vrm::spring_bone::{SpringJointProps, SpringJointState, SpringRoot};
+    use bevy::app::App;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{BuildChildren, Commands, Entity, GlobalTransform, Transform};
+    use bevy::utils::default;
+    use std::time::Duration;
+
+    /// Spawns `vrm -> joint -> tail` where the joint has been swung and its tail has velocity,
+    /// and returns the entities of the VRM and the joint.
+    fn spawn_swung_chain(app: &mut App) -> TestResult<(Entity, Entity)> {
+        let entities = app.world_mut().run_system_once(|mut commands: Commands| {
+            let vrm = commands
+                .spawn((Transform::default(), GlobalTransform::default()))
+                .id();
+            let joint = commands
+                .spawn((
+                    Transform::from_rotation(Quat::from_rotation_z(1.)),
+                    GlobalTransform::default(),
+                    SpringJointProps::default(),
+                    SpringJointState {
+                        prev_tail: Vec3::new(3., 0., 0.),
+                        current_tail: Vec3::new(1., 0., 0.),
+                        bone_axis: Vec3::NEG_Y,
+                        bone_length: 1.,
+                        // The joint resets to the animated rotation, which differs from the rest pose.
+                        initial_local_rotation: Quat::from_rotation_z(-1.),
+                        animated_rotation: Quat::IDENTITY,
+                        ..default()
+                    },
+                ))
+                .set_parent(vrm)
+                .id();
+            let tail = commands
+                .spawn((Transform::from_xyz(0., -1., 0.), GlobalTransform::default()))
+                .set_parent(joint)
+                .id();
+            commands.entity(joint).insert(SpringRoot {
+                joints: vec![joint, tail],
+                ..default()
+            });
+            (vrm, joint)
+        })?;
+        Ok(entities)
+    }
+
+    #[test]
+    fn reset_joints() -> TestResult {
+        let mut app = test_app();
+        app.add_observer(observe_reset_spring_bones);
+        let (vrm, joint) = spawn_swung_chain(&mut app)?;
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                commands.entity(vrm).trigger(ResetSpringBones);
+            })?;
+
+        let tf = app.world().get::<Transform>(joint).unwrap();
+        assert_eq!(tf.rotation, Quat::IDENTITY);
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        assert_eq!(state.prev_tail, state.current_tail);
+        Ok(())
+    }
+
+    #[test]
+    fn sleeping_chain_stays_asleep_after_reset() -> TestResult {
+        let mut app = spring_app();
+        app.add_observer(observe_reset_spring_bones);
+        let (vrm, joint) = spawn_swung_chain(&mut app)?;
+        // Consumes the changes of the spawned transforms.
+        step(&mut app, Duration::ZERO);
+        let mut lod = app.world_mut().get_mut::<SpringChainLod>(joint).unwrap();
+        lod.settle(
+            &SpringChainSolver::default(),
+            0.,
+            Duration::from_secs(2),
+            &SpringBoneLod::default(),
+        );
+        let animated_rotation = app
+            .world()
+            .get::<SpringJointState>(joint)
+            .unwrap()
+            .animated_rotation;
+
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                commands.entity(vrm).trigger(ResetSpringBones);
+            })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        assert!(app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert_eq!(state.animated_rotation, animated_rotation);
+        Ok(())
+    }
+
+    #[test]
+    fn reset_after_teleport() -> TestResult {
+        let mut app = test_app();
+        app.add_observer(observe_reset_spring_bones);
+        let (vrm, joint) = spawn_swung_chain(&mut app)?;
+        app.world_mut()
+            .entity_mut(vrm)
+            .insert(SpringBoneAutoReset::new(1.));
+        app.world_mut().run_system_once(detect_teleport)?;
+
+        app.world_mut()
+            .entity_mut(vrm)
+            .insert(GlobalTransform::from_xyz(10., 0., 0.));
+        app.world_mut().run_system_once(detect_teleport)?;
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert_eq!(state.prev_tail, state.current_tail);
+        Ok(())
+    }
+
+    #[test]
+    fn not_reset_below_threshold() -> TestResult {
+        let mut app = test_app();
+        app.add_observer(observe_reset_spring_bones);
+        let (vrm, joint) = spawn_swung_chain(&mut app)?;
+        app.world_mut()
+            .entity_mut(vrm)
+            .insert(SpringBoneAutoReset::new(1.));
+        app.world_mut().run_system_once(detect_teleport)?;
+
+        app.world_mut()
+            .entity_mut(vrm)
+            .insert(GlobalTransform::from_xyz(0.5, 0., 0.));
+        app.world_mut().run_system_once(detect_teleport)?;
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert_ne!(state.prev_tail, state.current_tail);
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+//! Captures and restores the whole spring bone simulation state of a VRM.
+//!
+//! The joints are addressed by their names, so a snapshot can be restored to the same VRM spawned again,
+//! such as after reloading the scene.
+
+use crate::vrm::spring_bone::lod::SpringChainLod;
+use crate::vrm::spring_bone::switch::SpringChainActivity;
+use crate::vrm::spring_bone::{SpringJointState, SpringRoot};
+use bevy::app::App;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct SpringBoneSnapshotPlugin;
+
+impl Plugin for SpringBoneSnapshotPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringBoneSnapshot>()
+            .register_type::<SpringJointSnapshot>()
+            .register_type::<SpringChainSnapshot>();
+    }
+}
+
+/// The spring bone simulation state of a VRM.
+///
+/// [`SpringBoneClock`](crate::vrm::spring_bone::SpringBoneClock) is shared by all VRMs and is not included,
+/// so clone it together if the remaining time of the fixed step also needs to be restored.
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+pub struct SpringBoneSnapshot {
+    /// The joints keyed by their names.
+    pub joints: BTreeMap<String, SpringJointSnapshot>,
+
+    /// The chains keyed by the names of their root joints.
+    pub chains: BTreeMap<String, SpringChainSnapshot>,
+}
+
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+pub struct SpringChainSnapshot {
+    pub activity: SpringChainActivity,
+
+    /// The level of detail of the chain, restored together so a sleeping chain stays asleep.
+    ///
+    /// The pose at which the chain fell asleep is stored relative to the VRM,
+    /// so the chain also stays asleep when restored to the VRM placed at another position.
+    pub lod: SpringChainLod,
+}
+
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+pub struct SpringJointSnapshot {
+    pub state: SpringJointState,
+
+    /// The local rotation of the joint, restored together so the pose does not jump.
+    pub rotation: Quat,
+}
+
+/// Captures and restores [`SpringBoneSnapshot`] of VRMs.
+#[derive(SystemParam)]
+pub struct SpringBoneSnapshots<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    names: Query<'w, 's, &'static Name>,
+    spring_roots: Query<
+        'w,
+        's,
+        (
+            &'static SpringRoot,
+            &'static mut SpringChainActivity,
+            &'static mut SpringChainLod,
+        ),
+    >,
+    joints: Query<'w, 's, (&'static Parent, &'static mut SpringJointState)>,
+    transforms: Query<'w, 's, (&'static mut Transform, &'static mut GlobalTransform)>,
+}
+
+impl SpringBoneSnapshots<'_, '_> {
+    /// Captures the spring bone state of the VRM.
+    pub fn capture(
+        &self,
+        vrm: Entity,
+    ) -> SpringBoneSnapshot {
+        let mut snapshot = SpringBoneSnapshot::default();
+        let vrm_inverse = self.vrm_matrix(vrm).inverse();
+        for descendant in self.children.iter_descendants(vrm) {
+            let Ok((spring_root, activity, lod)) = self.spring_roots.get(descendant) else {
+                continue;
+            };
+            if let Ok(name) = self.names.get(descendant) {
+                let mut lod = *lod;
+                lod.transform_anchor(vrm_inverse);
+                snapshot.chains.insert(
+                    name.to_string(),
+                    SpringChainSnapshot {
+                        activity: *activity,
+                        lod,
+                    },
+                );
+            }
+            for joint in spring_root.joints.iter().copied() {
+                let (Ok(name), Ok((_, state)), Ok((tf, _))) = (
+                    self.names.get(joint),
+                    self.joints.get(joint),
+                    self.transforms.get(joint),
+                ) else {
+                    continue;
+                };
+                snapshot.joints.insert(
+                    name.to_string(),
+                    SpringJointSnapshot {
+                        state: *state,
+                        rotation: tf.rotation,
+                    },
+                );
+            }
+        }
+        snapshot
+    }
+
+    /// Restores the spring bone state of the VRM from `snapshot`.
+    ///
+    /// The joints and the chains that are not in the snapshot are left as they are.
+    ///
+    /// The transforms of the joints are written without triggering change detection,
+    /// so the restored rotations are not regarded as animated and sleeping chains stay asleep.
+    pub fn restore(
+        &mut self,
+        vrm: Entity,
+        snapshot: &SpringBoneSnapshot,
+    ) {
+        let vrm_matrix = self.vrm_matrix(vrm);
+        let spring_roots = self
+            .children
+            .iter_descendants(vrm)
+            .filter(|entity| self.spring_roots.contains(*entity))
+            .collect::<Vec<_>>();
+        for root_entity in spring_roots {
+            let Ok((spring_root, mut activity, mut lod)) = self.spring_roots.get_mut(root_entity)
+            else {
+                continue;
+            };
+            if let Some(saved) = self
+                .names
+                .get(root_entity)
+                .ok()
+                .and_then(|name| snapshot.chains.get(name.as_str()))
+            {
+                *activity = saved.activity;
+                *lod = saved.lod;
+                lod.transform_anchor(vrm_matrix);
+            }
+            for joint in spring_root.joints.iter().copied() {
+                let Some(saved) = self
+                    .names
+                    .get(joint)
+                    .ok()
+                    .and_then(|name| snapshot.joints.get(name.as_str()))
+                else {
+                    continue;
+                };
+                let Ok((parent, mut state)) = self.joints.get_mut(joint) else {
+                    continue;
+                };
+                *state = saved.state;
+                // The global transform is updated immediately because the children of the joint depend on it.
+                let parent_gtf = self
+                    .transforms
+                    .get(parent.get())
+                    .map(|(_, gtf)| *gtf)
+                    .unwrap_or_default();
+                let Ok((mut tf, mut gtf)) = self.transforms.get_mut(joint) else {
+                    continue;
+                };
+                let tf = tf.bypass_change_detection();
+                tf.rotation = saved.rotation;
+                *gtf.bypass_change_detection() = parent_gtf.mul_transform(*tf);
+            }
+        }
+    }
+
+    fn vrm_matrix(
+        &self,
+        vrm: Entity,
+    ) -> Mat4 {
+        self.transforms
+            .get(vrm)
+            .map(|(_, gtf)| gtf.compute_matrix())
+            .unwrap_or(Mat4::IDENTITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::lod::{SpringBoneLod, SpringChainLod};
+    use crate::vrm::spring_bone::snapshot::{SpringBoneSnapshot, SpringBoneSnapshots};
+    use crate::vrm::spring_bone::solver::SpringChainSolver;
+    use crate::vrm::spring_bone::switch::SpringChainActivity;
+    use crate::vrm::spring_bone::update::tests::{spring_app, step};
+    use crate::vrm::spring_bone::{SpringJointProps, SpringJointState, SpringRoot};
+    use bevy::app::App;
+    use bevy::core::Name;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{BuildChildren, Commands, Entity, GlobalTransform, In, Parent, Transform};
+    use bevy::utils::default;
+    use std::time::Duration;
+
+    /// Spawns `vrm -> joint -> tail` and returns the entities of the VRM and the joint.
+    fn spawn_chain(app: &mut App) -> TestResult<(Entity, Entity)> {
+        spawn_chain_at(app, Vec3::ZERO)
+    }
+
+    /// Spawns `vrm -> joint -> tail` with the VRM at `vrm_pos`, and returns the entities of the VRM and the joint.
+    fn spawn_chain_at(
+        app: &mut App,
+        vrm_pos: Vec3,
+    ) -> TestResult<(Entity, Entity)> {
+        let entities = app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                let vrm = commands
+                    .spawn((
+                        Transform::from_translation(vrm_pos),
+                        GlobalTransform::from_translation(vrm_pos),
+                    ))
+                    .id();
+                let joint = commands
+                    .spawn((
+                        Name::new("joint"),
+                        Transform::from_rotation(Quat::from_rotation_z(1.)),
+                        GlobalTransform::default(),
+                        SpringJointProps::default(),
+                        SpringJointState {
+                            prev_tail: Vec3::new(3., 0., 0.),
+                            current_tail: Vec3::new(1., 0., 0.),
+                            bone_axis: Vec3::NEG_Y,
+                            bone_length: 1.,
+                            ..default()
+                        },
+                    ))
+                    .set_parent(vrm)
+                    .id();
+                let tail = commands
+                    .spawn((
+                        Name::new("tail"),
+                        Transform::from_xyz(0., -1., 0.),
+                        GlobalTransform::default(),
+                    ))
+                    .set_parent(joint)
+                    .id();
+                commands.entity(joint).insert(SpringRoot {
+                    joints: vec![joint, tail],
+                    ..default()
+                });
+                (vrm, joint)
+            })?;
+        Ok(entities)
+    }
+
+    fn capture(
+        In(vrm): In<Entity>,
+        snapshots: SpringBoneSnapshots,
+    ) -> SpringBoneSnapshot {
+        snapshots.capture(vrm)
+    }
+
+    fn restore(
+        In((vrm, snapshot)): In<(Entity, SpringBoneSnapshot)>,
+        mut snapshots: SpringBoneSnapshots,
+    ) {
+        snapshots.restore(vrm, &snapshot);
+    }
+
+    #[test]
+    fn restore_captured_state() -> TestResult {
+        let mut app = test_app();
+        let (vrm, joint) = spawn_chain(&mut app)?;
+        let snapshot = app.world_mut().run_system_once_with(vrm, capture)?;
+        let state = *app.world().get::<SpringJointState>(joint).unwrap();
+        let rotation = app.world().get::<Transform>(joint).unwrap().rotation;
+
+        *app.world_mut().get_mut::<SpringJointState>(joint).unwrap() = SpringJointState::default();
+        app.world_mut()
+            .get_mut::<Transform>(joint)
+            .unwrap()
+            .rotation = Quat::IDENTITY;
+        app.world_mut()
+            .get_mut::<SpringChainActivity>(joint)
+            .unwrap()
+            .enabled = false;
+        app.world_mut()
+            .run_system_once_with((vrm, snapshot), restore)?;
+
+        assert_eq!(app.world().get::<SpringJointState>(joint), Some(&state));
+        assert_eq!(
+            app.world().get::<Transform>(joint).unwrap().rotation,
+            rotation
+        );
+        assert_eq!(
+            app.world()
+                .get::<GlobalTransform>(joint)
+                .unwrap()
+                .rotation(),
+            rotation
+        );
+        assert!(
+            app.world()
+                .get::<SpringChainActivity>(joint)
+                .unwrap()
+                .enabled
+        );
+        success!()
+    }
+
+    #[test]
+    fn restore_sleeping_chain() -> TestResult {
+        let mut app = test_app();
+        let (vrm, joint) = spawn_chain(&mut app)?;
+        let mut lod = app.world_mut().get_mut::<SpringChainLod>(joint).unwrap();
+        lod.settle(
+            &SpringChainSolver::default(),
+            0.,
+            Duration::from_secs(2),
+            &SpringBoneLod::default(),
+        );
+        assert!(lod.is_asleep());
+        let snapshot = app.world_mut().run_system_once_with(vrm, capture)?;
+
+        *app.world_mut().get_mut::<SpringChainLod>(joint).unwrap() = SpringChainLod::default();
+        app.world_mut()
+            .run_system_once_with((vrm, snapshot), restore)?;
+
+        assert!(app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+        success!()
+    }
+
+    /// Puts the chain of `joint` to sleep at the current pose of its parent.
+    fn fall_asleep(
+        app: &mut App,
+        joint: Entity,
+    ) {
+        let parent_gtf = *app
+            .world()
+            .get::<GlobalTransform>(vrm_of(app, joint))
+            .unwrap();
+        let solver = SpringChainSolver {
+            center: parent_gtf.compute_matrix(),
+            ..default()
+        };
+        let mut lod = app.world_mut().get_mut::<SpringChainLod>(joint).unwrap();
+        lod.settle(
+            &solver,
+            0.,
+            Duration::from_secs(2),
+            &SpringBoneLod::default(),
+        );
+        assert!(lod.is_asleep());
+    }
+
+    fn vrm_of(
+        app: &App,
+        joint: Entity,
+    ) -> Entity {
+        app.world().get::<Parent>(joint).unwrap().get()
+    }
+
+    #[test]
+    fn restored_sleeping_chain_stays_asleep_after_update() -> TestResult {
+        let mut app = spring_app();
+        let (vrm, joint) = spawn_chain(&mut app)?;
+        // Consumes the changes of the spawned transforms.
+        step(&mut app, Duration::ZERO);
+        fall_asleep(&mut app, joint);
+        // The animated pose differs from the current rotation, so overwriting it is detected.
+        app.world_mut()
+            .get_mut::<SpringJointState>(joint)
+            .unwrap()
+            .animated_rotation = Quat::IDENTITY;
+        let snapshot = app.world_mut().run_system_once_with(vrm, capture)?;
+
+        *app.world_mut().get_mut::<SpringChainLod>(joint).unwrap() = SpringChainLod::default();
+        app.world_mut()
+            .run_system_once_with((vrm, snapshot.clone()), restore)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        assert!(app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+        assert_eq!(
+            app.world()
+                .get::<SpringJointState>(joint)
+                .unwrap()
+                .animated_rotation,
+            snapshot.joints["joint"].state.animated_rotation
+        );
+        success!()
+    }
+
+    #[test]
+    fn restore_sleeping_chain_to_moved_vrm() -> TestResult {
+        let mut app = spring_app();
+        let (vrm, joint) = spawn_chain(&mut app)?;
+        step(&mut app, Duration::ZERO);
+        fall_asleep(&mut app, joint);
+        let snapshot = app.world_mut().run_system_once_with(vrm, capture)?;
+
+        let mut app = spring_app();
+        let (vrm, joint) = spawn_chain_at(&mut app, Vec3::new(5., 0., 0.))?;
+        step(&mut app, Duration::ZERO);
+        app.world_mut()
+            .run_system_once_with((vrm, snapshot), restore)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        assert!(app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+        success!()
+    }
+
+    #[test]
+    fn restore_to_respawned_vrm() -> TestResult {
+        let mut app = test_app();
+        let (vrm, _) = spawn_chain(&mut app)?;
+        let snapshot = app.world_mut().run_system_once_with(vrm, capture)?;
+        let json = serde_json::to_string(&snapshot)?;
+
+        let mut app = test_app();
+        let (vrm, joint) = spawn_chain(&mut app)?;
+        *app.world_mut().get_mut::<SpringJointState>(joint).unwrap() = SpringJointState::default();
+        let snapshot: SpringBoneSnapshot = serde_json::from_str(&json)?;
+        app.world_mut()
+            .run_system_once_with((vrm, snapshot.clone()), restore)?;
+
+        assert_eq!(
+            app.world().get::<SpringJointState>(joint),
+            Some(&snapshot.joints["joint"].state)
+        );
+        success!()
+    }
+}
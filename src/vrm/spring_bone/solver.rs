@@ -0,0 +1,716 @@
+//! The spring bone simulation independent of the ECS.
+//!
+//! [`SpringChainSolver`] steps a spring chain from plain data,
+//! so the physics can be tested without an app and laid out contiguously.
+//! The benchmarks drive it through [`bench`](crate::vrm::spring_bone::bench).
+
+use crate::vrm::extensions::vrmc_spring_bone::{SpringColliderShape, SpringJointLimit};
+use crate::vrm::spring_bone::wind::WindField;
+use crate::vrm::spring_bone::{SpringJointProps, SpringJointState};
+use bevy::math::{Mat4, Quat, Vec3};
+use bevy::prelude::{GlobalTransform, Transform};
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+/// A joint of the chain simulated by [`SpringChainSolver`].
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct SpringSolverJoint {
+    pub state: SpringJointState,
+    pub props: SpringJointProps,
+
+    /// The local transform of the joint.
+    ///
+    /// The rotation is overwritten by the solver.
+    pub transform: Transform,
+
+    /// The global transform of the parent of the joint.
+    ///
+    /// `None` means the parent is the previous joint in the chain,
+    /// in which case the solved global transform of the previous joint is used.
+    pub parent: Option<GlobalTransform>,
+
+    /// The global transform of the joint computed by the solver.
+    pub global: GlobalTransform,
+
+    /// The limit of the direction of the joint applied after the collision.
+    pub limit: Option<SpringJointLimit>,
+}
+
+/// A joint whose parent is a joint of a preceding chain, such as the first joint of a branch chain.
+///
+/// The parent of the joint is updated from the solved pose of the preceding chain at every step.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct SpringChainLink {
+    /// The index of the joint in this chain.
+    pub joint: usize,
+
+    /// The number of chains back from this chain to the chain of the parent joint.
+    pub chains_back: usize,
+
+    /// The index of the parent joint in its chain.
+    pub parent_joint: usize,
+}
+
+/// A collider that the joints of [`SpringChainSolver`] collide with.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct SpringSolverCollider {
+    pub shape: SpringColliderShape,
+    pub transform: GlobalTransform,
+}
+
+/// Simulates a spring chain.
+///
+/// The joints must be ordered from the root of the chain to the tip.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SpringChainSolver {
+    /// The world matrix of the center node of the chain.
+    ///
+    /// The tails of the joints are stored in this space.
+    pub center: Mat4,
+    pub joints: Vec<SpringSolverJoint>,
+    pub colliders: Vec<SpringSolverCollider>,
+
+    /// The winds added to the external force of the joints.
+    pub wind: Vec<WindField>,
+
+    /// The simulated time in seconds at the start of the next step, used to evaluate the turbulence of the wind.
+    pub elapsed: f32,
+
+    /// The blend weight of the simulated pose over the animated pose of the joints.
+    pub weight: f32,
+
+    /// The multiplier of the delta time of each step.
+    ///
+    /// Chains far from cameras are stepped less often with a longer delta time.
+    pub time_scale: f32,
+
+    /// The joints whose parents are solved by preceding chains, used by [`advance_chains`].
+    pub links: Vec<SpringChainLink>,
+}
+
+impl Default for SpringChainSolver {
+    fn default() -> Self {
+        Self {
+            center: Mat4::IDENTITY,
+            joints: Vec::new(),
+            colliders: Vec::new(),
+            wind: Vec::new(),
+            elapsed: 0.,
+            weight: 1.,
+            time_scale: 1.,
+            links: Vec::new(),
+        }
+    }
+}
+
+impl SpringChainSolver {
+    /// Clears the chain, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.center = Mat4::IDENTITY;
+        self.joints.clear();
+        self.colliders.clear();
+        self.wind.clear();
+        self.elapsed = 0.;
+        self.weight = 1.;
+        self.time_scale = 1.;
+        self.links.clear();
+    }
+
+    /// Advances the simulation by `delta_time` seconds.
+    pub fn step(
+        &mut self,
+        delta_time: f32,
+    ) {
+        let center_inverse = self.center.inverse();
+        for i in 0..self.joints.len() {
+            let parent_gtf = self.parent_global(i);
+            let joint = &self.joints[i];
+            let state = &joint.state;
+            let props = &joint.props;
+            let joint_global_pos = parent_gtf.transform_point(joint.transform.translation);
+
+            // The tails are stored in the center space, so moving the center does not inject inertia.
+            let current_tail = self.center.transform_point3(state.current_tail);
+            let prev_tail = self.center.transform_point3(state.prev_tail);
+            let inertia = (current_tail - prev_tail) * (1. - props.drag_force);
+            let stiffness = delta_time
+                * (parent_gtf.rotation()
+                    * state.initial_local_rotation
+                    * state.bone_axis
+                    * props.stiffness);
+            let wind = self
+                .wind
+                .iter()
+                .map(|wind| wind.force_at(current_tail, self.elapsed))
+                .sum::<Vec3>();
+            let external = delta_time * (props.gravity_dir * props.gravity_power + wind);
+
+            let next_tail = current_tail + inertia + stiffness + external;
+            let mut next_tail =
+                joint_global_pos + (next_tail - joint_global_pos).normalize() * state.bone_length;
+            self.collision(
+                &mut next_tail,
+                joint_global_pos,
+                state.bone_length,
+                props.hit_radius,
+            );
+            if let Some(limit) = joint.limit {
+                let dir = limit.constrain(
+                    parent_gtf.rotation() * state.initial_local_rotation,
+                    state.bone_axis,
+                    (next_tail - joint_global_pos).normalize(),
+                );
+                next_tail = joint_global_pos + dir * state.bone_length;
+            }
+
+            let joint = &mut self.joints[i];
+            joint.state.prev_tail = joint.state.current_tail;
+            joint.state.current_tail = center_inverse.transform_point3(next_tail);
+            joint.apply_rotation(&parent_gtf, next_tail, self.weight);
+        }
+        self.elapsed += delta_time;
+    }
+
+    /// Poses the joints by the tails interpolated between the last two steps.
+    ///
+    /// `overstep_fraction` is the ratio from the previous tail to the current tail.
+    pub fn interpolate(
+        &mut self,
+        overstep_fraction: f32,
+    ) {
+        for i in 0..self.joints.len() {
+            let parent_gtf = self.parent_global(i);
+            let joint = &mut self.joints[i];
+            let tail = self.center.transform_point3(
+                joint
+                    .state
+                    .prev_tail
+                    .lerp(joint.state.current_tail, overstep_fraction),
+            );
+            joint.apply_rotation(&parent_gtf, tail, self.weight);
+        }
+    }
+
+    /// Poses the joints by their animated rotations, and places the tails there without velocity.
+    pub fn reset_tails(&mut self) {
+        let center_inverse = self.center.inverse();
+        for i in 0..self.joints.len() {
+            let parent_gtf = self.parent_global(i);
+            let joint = &mut self.joints[i];
+            joint.transform.rotation = joint.state.animated_rotation;
+            joint.global = parent_gtf.mul_transform(joint.transform);
+            let tail = joint.global.translation()
+                + joint.global.rotation() * joint.state.bone_axis * joint.state.bone_length;
+            let tail = center_inverse.transform_point3(tail);
+            joint.state.prev_tail = tail;
+            joint.state.current_tail = tail;
+        }
+    }
+
+    /// Returns the fastest speed of the tails in the last step of `delta_time` seconds.
+    pub fn tail_speed(
+        &self,
+        delta_time: f32,
+    ) -> f32 {
+        self.joints
+            .iter()
+            .map(|joint| joint.state.current_tail.distance(joint.state.prev_tail))
+            .fold(0., f32::max)
+            / (delta_time * self.time_scale)
+    }
+
+    /// Returns `true` if any tail is inside the colliders beyond the tolerance of the collision.
+    pub fn is_touching_colliders(&self) -> bool {
+        self.joints.iter().any(|joint| {
+            let tail = self.center.transform_point3(joint.state.current_tail);
+            self.colliders.iter().any(|collider| {
+                let (_, distance) = collider.shape.calc_collision(
+                    tail,
+                    &collider.transform,
+                    joint.props.hit_radius,
+                );
+                distance < -TOUCH_TOLERANCE
+            })
+        })
+    }
+
+    fn parent_global(
+        &self,
+        index: usize,
+    ) -> GlobalTransform {
+        match self.joints[index].parent {
+            Some(parent) => parent,
+            None => index
+                .checked_sub(1)
+                .map(|previous| self.joints[previous].global)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Pushes the tail out of the colliders, treating the tail as a sphere of `hit_radius`.
+    fn collision(
+        &self,
+        next_tail: &mut Vec3,
+        joint_global_pos: Vec3,
+        bone_length: f32,
+        hit_radius: f32,
+    ) {
+        for collider in self.colliders.iter() {
+            let (dir, distance) =
+                collider
+                    .shape
+                    .calc_collision(*next_tail, &collider.transform, hit_radius);
+            if distance < 0. {
+                *next_tail -= dir * distance;
+                *next_tail =
+                    joint_global_pos + (*next_tail - joint_global_pos).normalize() * bone_length;
+            }
+        }
+    }
+}
+
+/// The penetration depth regarded as touching a collider.
+///
+/// Tails resting on a collider may sink slightly after being kept at the bone length.
+const TOUCH_TOLERANCE: f32 = 1e-3;
+
+/// Steps the chains in order `steps` times by `delta_time` seconds,
+/// and then poses the joints by [`SpringChainSolver::interpolate`] if `overstep_fraction` is given.
+///
+/// The chains are stepped together, so the linked joints follow the parent chains solved in the same step.
+/// The parent chains must precede the linked chains in `solvers`.
+pub(crate) fn advance_chains(
+    solvers: &mut [SpringChainSolver],
+    steps: u32,
+    delta_time: f32,
+    overstep_fraction: Option<f32>,
+) {
+    for _ in 0..steps {
+        for i in 0..solvers.len() {
+            follow_links(solvers, i);
+            let solver = &mut solvers[i];
+            solver.step(delta_time * solver.time_scale);
+        }
+    }
+    if let Some(overstep_fraction) = overstep_fraction {
+        for i in 0..solvers.len() {
+            follow_links(solvers, i);
+            solvers[i].interpolate(overstep_fraction);
+        }
+    }
+}
+
+/// Advances the chains in parallel on the [`ComputeTaskPool`].
+///
+/// The chains are split into batches that no link crosses, and each batch is advanced by [`advance_chains`],
+/// so the results are identical to calling [`advance_chains`] on all chains.
+///
+/// `splittable` is the buffer reused across calls to avoid allocations.
+pub(crate) fn par_advance_chains(
+    solvers: &mut [SpringChainSolver],
+    splittable: &mut Vec<bool>,
+    steps: u32,
+    delta_time: f32,
+    overstep_fraction: Option<f32>,
+) {
+    if solvers.is_empty() || (steps == 0 && overstep_fraction.is_none()) {
+        return;
+    }
+    // `splittable[i]` is `true` if no chain from `i` links to a chain before `i`.
+    splittable.clear();
+    splittable.resize(solvers.len(), false);
+    let mut first_parent = solvers.len();
+    for i in (0..solvers.len()).rev() {
+        for link in solvers[i].links.iter() {
+            first_parent = first_parent.min(i.saturating_sub(link.chains_back));
+        }
+        splittable[i] = i <= first_parent;
+    }
+    let splittable = &*splittable;
+    let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let batch_size = solvers.len().div_ceil(pool.thread_num().max(1));
+    pool.scope(|scope| {
+        let mut rest = solvers;
+        while !rest.is_empty() {
+            let offset = splittable.len() - rest.len();
+            let len = (batch_size..rest.len())
+                .find(|len| splittable[offset + len])
+                .unwrap_or(rest.len());
+            let (batch, tail) = rest.split_at_mut(len);
+            rest = tail;
+            scope.spawn(async move {
+                advance_chains(batch, steps, delta_time, overstep_fraction);
+            });
+        }
+    });
+}
+
+/// Updates the parents of the linked joints of `solvers[index]` from the preceding chains.
+fn follow_links(
+    solvers: &mut [SpringChainSolver],
+    index: usize,
+) {
+    let (preceding, rest) = solvers.split_at_mut(index);
+    let solver = &mut rest[0];
+    for link in solver.links.iter() {
+        let Some(parent) = index
+            .checked_sub(link.chains_back)
+            .and_then(|chain| preceding.get(chain))
+            .and_then(|chain| chain.joints.get(link.parent_joint))
+        else {
+            continue;
+        };
+        if let Some(joint) = solver.joints.get_mut(link.joint) {
+            joint.parent = Some(parent.global);
+        }
+    }
+}
+
+impl SpringSolverJoint {
+    /// Rotates the joint to point at `tail`, blended with the animated rotation by `weight`.
+    fn apply_rotation(
+        &mut self,
+        parent_gtf: &GlobalTransform,
+        tail: Vec3,
+        weight: f32,
+    ) {
+        let to = (parent_gtf.compute_matrix() * self.state.initial_local_matrix)
+            .inverse()
+            .transform_point3(tail)
+            .normalize();
+        let rotation =
+            self.state.initial_local_rotation * Quat::from_rotation_arc(self.state.bone_axis, to);
+        self.transform.rotation = if weight < 1. {
+            self.state.animated_rotation.slerp(rotation, weight)
+        } else {
+            rotation
+        };
+        self.global = parent_gtf.mul_transform(self.transform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::extensions::vrmc_spring_bone::{
+        ConeLimit, Sphere, SpringColliderShape, SpringJointLimit,
+    };
+    use crate::vrm::spring_bone::solver::{
+        advance_chains, par_advance_chains, SpringChainLink, SpringChainSolver,
+        SpringSolverCollider, SpringSolverJoint,
+    };
+    use crate::vrm::spring_bone::wind::{Turbulence, WindField, WindFieldShape};
+    use crate::vrm::spring_bone::{SpringJointProps, SpringJointState};
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{GlobalTransform, Transform};
+    use bevy::utils::default;
+
+    /// Creates a joint hanging down by 1 from `transform`.
+    fn hanging_joint(
+        transform: Transform,
+        parent: Option<GlobalTransform>,
+        props: SpringJointProps,
+    ) -> SpringSolverJoint {
+        let parent_gtf = parent.unwrap_or_default();
+        let tail = parent_gtf.transform_point(transform.translation) + Vec3::NEG_Y;
+        SpringSolverJoint {
+            state: SpringJointState {
+                prev_tail: tail,
+                current_tail: tail,
+                bone_axis: Vec3::NEG_Y,
+                bone_length: 1.,
+                initial_local_matrix: transform.compute_matrix(),
+                initial_local_rotation: transform.rotation,
+                animated_rotation: transform.rotation,
+            },
+            props,
+            transform,
+            parent,
+            global: parent_gtf.mul_transform(transform),
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn gravity_pulls_tail() {
+        let props = SpringJointProps {
+            stiffness: 0.,
+            gravity_dir: Vec3::X,
+            gravity_power: 1.,
+            ..default()
+        };
+        let mut solver = SpringChainSolver {
+            joints: vec![hanging_joint(
+                Transform::default(),
+                Some(GlobalTransform::default()),
+                props,
+            )],
+            ..default()
+        };
+        solver.step(0.1);
+
+        let joint = &solver.joints[0];
+        assert!(0. < joint.state.current_tail.x);
+        assert!((joint.state.current_tail.length() - 1.).abs() < 1e-5);
+        assert_ne!(joint.transform.rotation, Quat::IDENTITY);
+    }
+
+    #[test]
+    fn resting_chain_stays_still() {
+        let mut solver = SpringChainSolver {
+            joints: vec![
+                hanging_joint(
+                    Transform::default(),
+                    Some(GlobalTransform::default()),
+                    default(),
+                ),
+                hanging_joint(Transform::from_xyz(0., -1., 0.), None, default()),
+            ],
+            ..default()
+        };
+        for _ in 0..10 {
+            solver.step(1. / 60.);
+        }
+
+        assert!(solver.joints[0]
+            .state
+            .current_tail
+            .abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        assert!(solver.joints[1]
+            .state
+            .current_tail
+            .abs_diff_eq(Vec3::new(0., -2., 0.), 1e-5));
+    }
+
+    #[test]
+    fn child_follows_solved_parent() {
+        let props = SpringJointProps {
+            stiffness: 0.,
+            gravity_dir: Vec3::X,
+            gravity_power: 1.,
+            ..default()
+        };
+        let mut solver = SpringChainSolver {
+            joints: vec![
+                hanging_joint(
+                    Transform::default(),
+                    Some(GlobalTransform::default()),
+                    props,
+                ),
+                hanging_joint(Transform::from_xyz(0., -1., 0.), None, props),
+            ],
+            ..default()
+        };
+        solver.step(0.1);
+
+        let root_tail = solver.joints[0].state.current_tail;
+        let child_pos = solver.joints[1].global.translation();
+        assert!(root_tail.abs_diff_eq(child_pos, 1e-5));
+        let child_bone = solver.joints[1].state.current_tail - child_pos;
+        assert!((child_bone.length() - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn push_tail_out_of_collider() {
+        let mut solver = SpringChainSolver {
+            joints: vec![hanging_joint(
+                Transform::default(),
+                Some(GlobalTransform::default()),
+                SpringJointProps {
+                    stiffness: 0.,
+                    hit_radius: 0.1,
+                    ..default()
+                },
+            )],
+            colliders: vec![SpringSolverCollider {
+                shape: SpringColliderShape::Sphere(Sphere {
+                    offset: [0., 0., 0.],
+                    radius: 0.5,
+                }),
+                transform: GlobalTransform::from_xyz(0.3, -1., 0.),
+            }],
+            ..default()
+        };
+        solver.step(1. / 60.);
+
+        let tail = solver.joints[0].state.current_tail;
+        assert!(tail.x < -0.2);
+        assert!((tail.length() - 1.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn interpolate_between_tails() {
+        let mut joint = hanging_joint(
+            Transform::default(),
+            Some(GlobalTransform::default()),
+            default(),
+        );
+        joint.state.prev_tail = Vec3::NEG_Y;
+        joint.state.current_tail = Vec3::X;
+        let mut solver = SpringChainSolver {
+            joints: vec![joint],
+            ..default()
+        };
+        solver.interpolate(0.5);
+
+        let bone = solver.joints[0].transform.rotation * Vec3::NEG_Y;
+        assert!(bone.abs_diff_eq(Vec3::new(1., -1., 0.).normalize(), 1e-5));
+    }
+
+    #[test]
+    fn parallel_results_are_identical_to_sequential() {
+        let mut solvers = (0..32)
+            .map(|i| {
+                let props = SpringJointProps {
+                    gravity_dir: Vec3::new(i as f32, -1., 0.).normalize(),
+                    gravity_power: 0.1 * i as f32,
+                    ..default()
+                };
+                SpringChainSolver {
+                    joints: vec![
+                        hanging_joint(
+                            Transform::from_xyz(i as f32, 0., 0.),
+                            Some(GlobalTransform::default()),
+                            props,
+                        ),
+                        hanging_joint(Transform::from_xyz(0., -1., 0.), None, props),
+                    ],
+                    colliders: vec![SpringSolverCollider {
+                        shape: SpringColliderShape::Sphere(Sphere {
+                            offset: [0., 0., 0.],
+                            radius: 0.3,
+                        }),
+                        transform: GlobalTransform::from_xyz(i as f32 + 0.5, -1.5, 0.),
+                    }],
+                    ..default()
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut sequential = solvers.clone();
+
+        for _ in 0..10 {
+            par_advance_chains(&mut solvers, &mut Vec::new(), 3, 1. / 60., Some(0.5));
+            for solver in sequential.iter_mut() {
+                advance_chains(std::slice::from_mut(solver), 3, 1. / 60., Some(0.5));
+            }
+        }
+        assert_eq!(solvers, sequential);
+    }
+
+    /// Creates pairs of a chain and a branch hanging from the first joint of the chain.
+    fn branched_chains() -> Vec<SpringChainSolver> {
+        let props = SpringJointProps {
+            stiffness: 0.,
+            gravity_dir: Vec3::X,
+            gravity_power: 1.,
+            ..default()
+        };
+        (0..16)
+            .flat_map(|i| {
+                let root = GlobalTransform::from_xyz(i as f32, 0., 0.);
+                let chain = SpringChainSolver {
+                    joints: vec![
+                        hanging_joint(Transform::default(), Some(root), props),
+                        hanging_joint(Transform::from_xyz(0., -1., 0.), None, props),
+                    ],
+                    ..default()
+                };
+                // The branch is gathered with the pose of the parent before solving.
+                let branch = SpringChainSolver {
+                    joints: vec![hanging_joint(
+                        Transform::from_xyz(0., -0.5, 0.),
+                        Some(root),
+                        props,
+                    )],
+                    links: vec![SpringChainLink {
+                        joint: 0,
+                        chains_back: 1,
+                        parent_joint: 0,
+                    }],
+                    ..default()
+                };
+                [chain, branch]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn branch_follows_solved_parent_chain() {
+        let mut solvers = branched_chains();
+        advance_chains(&mut solvers[..2], 1, 0.1, None);
+
+        let parent = solvers[0].joints[0].global;
+        let branch = &solvers[1].joints[0];
+        assert_ne!(parent.rotation(), Quat::IDENTITY);
+        assert_eq!(branch.parent, Some(parent));
+        assert!(branch
+            .global
+            .translation()
+            .abs_diff_eq(parent.transform_point(Vec3::new(0., -0.5, 0.)), 1e-5));
+    }
+
+    #[test]
+    fn parallel_branches_are_identical_to_sequential() {
+        let mut solvers = branched_chains();
+        let mut sequential = solvers.clone();
+
+        for _ in 0..10 {
+            par_advance_chains(&mut solvers, &mut Vec::new(), 3, 1. / 60., Some(0.5));
+            advance_chains(&mut sequential, 3, 1. / 60., Some(0.5));
+        }
+        assert_eq!(solvers, sequential);
+    }
+
+    #[test]
+    fn wind_pushes_tail() {
+        let mut solver = SpringChainSolver {
+            joints: vec![hanging_joint(
+                Transform::default(),
+                Some(GlobalTransform::default()),
+                SpringJointProps {
+                    stiffness: 0.,
+                    ..default()
+                },
+            )],
+            wind: vec![WindField {
+                shape: WindFieldShape::Directional {
+                    direction: Vec3::NEG_Z,
+                },
+                strength: 1.,
+                turbulence: Turbulence::default(),
+            }],
+            ..default()
+        };
+        solver.step(0.1);
+
+        assert!(solver.joints[0].state.current_tail.z < 0.);
+        assert!((solver.elapsed - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cone_limit_holds_tail() {
+        let mut joint = hanging_joint(
+            Transform::default(),
+            Some(GlobalTransform::default()),
+            SpringJointProps {
+                stiffness: 0.,
+                gravity_dir: Vec3::X,
+                gravity_power: 10.,
+                ..default()
+            },
+        );
+        joint.limit = Some(SpringJointLimit::Cone(ConeLimit {
+            angle: 0.5,
+            rotation: [0., 0., 0., 1.],
+        }));
+        let mut solver = SpringChainSolver {
+            joints: vec![joint],
+            ..default()
+        };
+        for _ in 0..10 {
+            solver.step(1. / 60.);
+        }
+
+        let tail = solver.joints[0].state.current_tail;
+        assert!((tail.angle_between(Vec3::NEG_Y) - 0.5).abs() < 1e-4);
+        assert!((tail.length() - 1.).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,354 @@
+//! Enables, disables and pauses spring chains and collider groups at runtime.
+
+use crate::macros::marker_component;
+use crate::system_param::child_searcher::ChildSearcher;
+use crate::vrm::spring_bone::attach::{AttachedColliderShapes, AttachedSpringRoots};
+use crate::vrm::spring_bone::registry::SpringColliderGroupRegistry;
+use crate::vrm::spring_bone::update::update_spring_bones;
+use crate::vrm::spring_bone::SpringRoot;
+use bevy::app::{App, PostUpdate};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+pub struct SpringBoneSwitchPlugin;
+
+impl Plugin for SpringBoneSwitchPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringBoneSwitches>()
+            .register_type::<SwitchSpringBones>()
+            .register_type::<SpringChainActivity>()
+            .register_type::<DisabledSpringCollider>()
+            .add_observer(observe_switch_spring_bones)
+            .add_systems(PostUpdate, sync_spring_switches.before(update_spring_bones));
+    }
+}
+
+/// The switches of the spring chains and the collider groups of the VRM.
+///
+/// This is attached to the VRM entity.
+/// The springs and the collider groups are addressed by their names in `VRMC_springBone`.
+/// If a collider belongs to several groups, disabling any of them disables the collider.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct SpringBoneSwitches {
+    /// If `false`, all spring chains of the VRM are disabled.
+    pub enabled: bool,
+
+    /// If `true`, all spring chains of the VRM hold their current pose without simulating.
+    pub paused: bool,
+
+    pub disabled_springs: BTreeSet<String>,
+    pub disabled_collider_groups: BTreeSet<String>,
+
+    /// The duration for disabled chains to blend back to the animated pose, and vice versa.
+    pub blend_duration: Duration,
+}
+
+impl Default for SpringBoneSwitches {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            paused: false,
+            disabled_springs: BTreeSet::new(),
+            disabled_collider_groups: BTreeSet::new(),
+            blend_duration: Duration::from_millis(300),
+        }
+    }
+}
+
+/// The trigger event to switch the spring bones of the VRM.
+///
+/// The event updates [`SpringBoneSwitches`] of the VRM, inserting it if it does not exist.
+#[derive(Event, Debug, Reflect, Clone, PartialEq)]
+pub enum SwitchSpringBones {
+    Enable,
+    Disable,
+    Pause,
+    Resume,
+    EnableSpring(String),
+    DisableSpring(String),
+    EnableColliderGroup(String),
+    DisableColliderGroup(String),
+}
+
+impl SpringBoneSwitches {
+    fn apply(
+        &mut self,
+        switch: &SwitchSpringBones,
+    ) {
+        match switch {
+            SwitchSpringBones::Enable => self.enabled = true,
+            SwitchSpringBones::Disable => self.enabled = false,
+            SwitchSpringBones::Pause => self.paused = true,
+            SwitchSpringBones::Resume => self.paused = false,
+            SwitchSpringBones::EnableSpring(name) => {
+                self.disabled_springs.remove(name);
+            }
+            SwitchSpringBones::DisableSpring(name) => {
+                self.disabled_springs.insert(name.clone());
+            }
+            SwitchSpringBones::EnableColliderGroup(name) => {
+                self.disabled_collider_groups.remove(name);
+            }
+            SwitchSpringBones::DisableColliderGroup(name) => {
+                self.disabled_collider_groups.insert(name.clone());
+            }
+        }
+    }
+}
+
+/// The activity of the spring chain.
+///
+/// This is attached to the root entity of the chain together with [`SpringRoot`],
+/// and is updated from [`SpringBoneSwitches`] of the VRM.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct SpringChainActivity {
+    /// If `false`, the chain blends back to the animated pose and then stops simulating.
+    pub enabled: bool,
+
+    /// If `true`, the chain holds its current pose without simulating.
+    pub paused: bool,
+
+    /// The duration to blend between the animated pose and the simulated pose.
+    pub blend_duration: Duration,
+
+    weight: f32,
+}
+
+impl SpringChainActivity {
+    /// Returns the blend weight of the simulated pose over the animated pose.
+    #[inline]
+    pub const fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Returns `true` if the chain has fully blended back to the animated pose.
+    #[inline]
+    pub fn is_sleeping(&self) -> bool {
+        self.weight <= 0.
+    }
+
+    /// Moves the weight toward the target by `delta`.
+    pub(crate) fn blend(
+        &mut self,
+        delta: Duration,
+    ) {
+        let target = if self.enabled { 1. } else { 0. };
+        if self.blend_duration.is_zero() {
+            self.weight = target;
+            return;
+        }
+        let step = delta.as_secs_f32() / self.blend_duration.as_secs_f32();
+        self.weight = if self.weight < target {
+            (self.weight + step).min(target)
+        } else {
+            (self.weight - step).max(target)
+        };
+    }
+}
+
+impl Default for SpringChainActivity {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            paused: false,
+            blend_duration: Duration::from_millis(300),
+            weight: 1.,
+        }
+    }
+}
+
+marker_component!(
+    /// A marker component that indicates that the collider is disabled by [`SpringBoneSwitches`].
+    ///
+    /// This is attached to the collider entity.
+    DisabledSpringCollider
+);
+
+fn observe_switch_spring_bones(
+    trigger: Trigger<SwitchSpringBones>,
+    mut commands: Commands,
+    mut vrms: Query<Option<&mut SpringBoneSwitches>>,
+) {
+    let Ok(switches) = vrms.get_mut(trigger.entity()) else {
+        return;
+    };
+    match switches {
+        Some(mut switches) => switches.apply(trigger.event()),
+        None => {
+            let mut switches = SpringBoneSwitches::default();
+            switches.apply(trigger.event());
+            commands.entity(trigger.entity()).insert(switches);
+        }
+    }
+}
+
+fn sync_spring_switches(
+    mut commands: Commands,
+    child_searcher: ChildSearcher,
+    vrms: Query<
+        (
+            Entity,
+            &SpringBoneSwitches,
+            Option<&SpringColliderGroupRegistry>,
+        ),
+        Or<(
+            Changed<SpringBoneSwitches>,
+            Added<AttachedSpringRoots>,
+            Added<AttachedColliderShapes>,
+        )>,
+    >,
+    children: Query<&Children>,
+    mut spring_roots: Query<(&SpringRoot, &mut SpringChainActivity)>,
+) {
+    for (vrm_entity, switches, collider_groups) in vrms.iter() {
+        for descendant in children.iter_descendants(vrm_entity) {
+            let Ok((spring_root, mut activity)) = spring_roots.get_mut(descendant) else {
+                continue;
+            };
+            activity.enabled =
+                switches.enabled && !switches.disabled_springs.contains(&spring_root.name);
+            activity.paused = switches.paused;
+            activity.blend_duration = switches.blend_duration;
+        }
+
+        let Some(collider_groups) = collider_groups else {
+            continue;
+        };
+        let disabled = collider_groups
+            .iter()
+            .filter(|(group, _)| switches.disabled_collider_groups.contains(*group))
+            .flat_map(|(_, colliders)| colliders.iter())
+            .collect::<HashSet<_>>();
+        for name in collider_groups.values().flatten() {
+            let Some(collider) = child_searcher.find_from_name(vrm_entity, name) else {
+                continue;
+            };
+            if disabled.contains(name) {
+                commands.entity(collider).insert(DisabledSpringCollider);
+            } else {
+                commands.entity(collider).remove::<DisabledSpringCollider>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::registry::SpringColliderGroupRegistry;
+    use crate::vrm::spring_bone::switch::{
+        observe_switch_spring_bones, sync_spring_switches, DisabledSpringCollider,
+        SpringBoneSwitches, SpringChainActivity, SwitchSpringBones,
+    };
+    use crate::vrm::spring_bone::SpringRoot;
+    use bevy::core::Name;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::{BuildChildren, Commands, Entity};
+    use bevy::utils::{default, HashMap};
+    use std::time::Duration;
+
+    #[test]
+    fn blend_weight_over_duration() {
+        let mut activity = SpringChainActivity {
+            enabled: false,
+            blend_duration: Duration::from_secs(1),
+            ..default()
+        };
+        activity.blend(Duration::from_millis(250));
+        assert!((activity.weight() - 0.75).abs() < 1e-5);
+        activity.blend(Duration::from_secs(2));
+        assert!(activity.is_sleeping());
+
+        activity.enabled = true;
+        activity.blend(Duration::from_millis(500));
+        assert!((activity.weight() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn disable_spring_by_name() -> TestResult {
+        let mut app = test_app();
+        app.add_observer(observe_switch_spring_bones);
+        let (vrm, hair, skirt): (Entity, Entity, Entity) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                let hair = commands
+                    .spawn(SpringRoot {
+                        name: "Hair".to_string(),
+                        ..default()
+                    })
+                    .id();
+                let skirt = commands
+                    .spawn(SpringRoot {
+                        name: "Skirt".to_string(),
+                        ..default()
+                    })
+                    .id();
+                let vrm = commands.spawn_empty().add_children(&[hair, skirt]).id();
+                (vrm, hair, skirt)
+            })?;
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                commands
+                    .entity(vrm)
+                    .trigger(SwitchSpringBones::DisableSpring("Hair".to_string()));
+            })?;
+        app.world_mut().run_system_once(sync_spring_switches)?;
+
+        assert!(
+            !app.world()
+                .get::<SpringChainActivity>(hair)
+                .unwrap()
+                .enabled
+        );
+        assert!(
+            app.world()
+                .get::<SpringChainActivity>(skirt)
+                .unwrap()
+                .enabled
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn toggle_collider_group() -> TestResult {
+        let mut app = test_app();
+        let (vrm, leg, head): (Entity, Entity, Entity) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                let leg = commands.spawn(Name::new("leg")).id();
+                let head = commands.spawn(Name::new("head")).id();
+                let vrm = commands
+                    .spawn((
+                        SpringBoneSwitches {
+                            disabled_collider_groups: ["Legs".to_string()].into(),
+                            ..default()
+                        },
+                        SpringColliderGroupRegistry(HashMap::from_iter([
+                            ("Legs".to_string(), vec![Name::new("leg")]),
+                            ("Head".to_string(), vec![Name::new("head")]),
+                        ])),
+                    ))
+                    .add_children(&[leg, head])
+                    .id();
+                (vrm, leg, head)
+            })?;
+        app.world_mut().run_system_once(sync_spring_switches)?;
+        assert!(app.world().get::<DisabledSpringCollider>(leg).is_some());
+        assert!(app.world().get::<DisabledSpringCollider>(head).is_none());
+
+        app.world_mut()
+            .get_mut::<SpringBoneSwitches>(vrm)
+            .unwrap()
+            .disabled_collider_groups
+            .clear();
+        app.world_mut().run_system_once(sync_spring_switches)?;
+        assert!(app.world().get::<DisabledSpringCollider>(leg).is_none());
+        Ok(())
+    }
+}
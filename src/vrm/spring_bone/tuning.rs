@@ -0,0 +1,363 @@
+//! Tunes the properties of spring chains at runtime.
+//!
+//! The chains are addressed by the name of the spring in `VRMC_springBone`,
+//! and the tunings can be saved to and loaded from a JSON preset keyed by the name of the VRM.
+
+use crate::error::AppResult;
+use crate::vrm::spring_bone::attach::{AttachedJointProps, AttachedSpringRoots};
+use crate::vrm::spring_bone::registry::SpringJointPropsRegistry;
+use crate::vrm::spring_bone::{SpringJointProps, SpringRoot};
+use crate::vrm::Vrm;
+use bevy::app::{App, Update};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub struct SpringBoneTuningPlugin;
+
+impl Plugin for SpringBoneTuningPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringBoneTuning>()
+            .register_type::<SpringChainTuning>()
+            .register_type::<SpringPropsMultiplier>()
+            .register_type::<SpringPropsOverride>()
+            .register_type::<SpringBonePresets>()
+            .init_resource::<SpringBonePresets>()
+            .add_systems(
+                Update,
+                (insert_preset_tuning, apply_spring_bone_tuning).chain(),
+            );
+    }
+}
+
+/// The tunings of the spring chains of the VRM, keyed by the name of the spring.
+///
+/// This is attached to the VRM entity.
+/// The chains without a tuning keep the properties defined in the VRM,
+/// and springs sharing the same name are tuned together.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+pub struct SpringBoneTuning {
+    pub chains: BTreeMap<String, SpringChainTuning>,
+}
+
+impl SpringBoneTuning {
+    /// Returns the tuning of the spring named `name`, inserting the default tuning if it does not exist.
+    pub fn chain_mut(
+        &mut self,
+        name: impl Into<String>,
+    ) -> &mut SpringChainTuning {
+        self.chains.entry(name.into()).or_default()
+    }
+}
+
+/// The tuning applied to every joint of a spring chain.
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SpringChainTuning {
+    pub multiplier: SpringPropsMultiplier,
+    pub overrides: SpringPropsOverride,
+}
+
+impl SpringChainTuning {
+    /// Returns the properties tuned from `props` defined in the VRM.
+    ///
+    /// The overrides take precedence over the multipliers.
+    pub fn apply(
+        &self,
+        props: &SpringJointProps,
+    ) -> SpringJointProps {
+        let multiplier = &self.multiplier;
+        let overrides = &self.overrides;
+        SpringJointProps {
+            drag_force: overrides
+                .drag_force
+                .unwrap_or(props.drag_force * multiplier.drag_force)
+                .clamp(0., 1.),
+            gravity_dir: overrides.gravity_dir.unwrap_or(props.gravity_dir),
+            gravity_power: overrides
+                .gravity_power
+                .unwrap_or(props.gravity_power * multiplier.gravity_power),
+            hit_radius: overrides
+                .hit_radius
+                .unwrap_or(props.hit_radius * multiplier.hit_radius),
+            stiffness: overrides
+                .stiffness
+                .unwrap_or(props.stiffness * multiplier.stiffness),
+        }
+    }
+}
+
+/// The factors multiplied to the properties defined in the VRM.
+#[derive(Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SpringPropsMultiplier {
+    pub drag_force: f32,
+    pub gravity_power: f32,
+    pub hit_radius: f32,
+    pub stiffness: f32,
+}
+
+impl Default for SpringPropsMultiplier {
+    fn default() -> Self {
+        Self {
+            drag_force: 1.,
+            gravity_power: 1.,
+            hit_radius: 1.,
+            stiffness: 1.,
+        }
+    }
+}
+
+/// The values that replace the properties defined in the VRM.
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SpringPropsOverride {
+    pub drag_force: Option<f32>,
+    pub gravity_dir: Option<Vec3>,
+    pub gravity_power: Option<f32>,
+    pub hit_radius: Option<f32>,
+    pub stiffness: Option<f32>,
+}
+
+/// The presets of [`SpringBoneTuning`] keyed by the name of the VRM.
+///
+/// When a VRM whose [`Name`] has a preset is spawned, the preset is attached to it as [`SpringBoneTuning`].
+#[derive(Resource, Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct SpringBonePresets(pub BTreeMap<String, SpringBoneTuning>);
+
+impl SpringBonePresets {
+    /// Loads the presets from the JSON file.
+    pub fn load(path: impl AsRef<Path>) -> AppResult<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Saves the presets to the JSON file.
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> AppResult {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn insert_preset_tuning(
+    mut commands: Commands,
+    presets: Res<SpringBonePresets>,
+    vrms: Query<(Entity, &Name), (With<Vrm>, Without<SpringBoneTuning>)>,
+) {
+    for (entity, name) in vrms.iter() {
+        let Some(tuning) = presets.0.get(name.as_str()) else {
+            continue;
+        };
+        commands.entity(entity).insert(tuning.clone());
+    }
+}
+
+fn apply_spring_bone_tuning(
+    vrms: Query<
+        (Entity, &SpringBoneTuning, &SpringJointPropsRegistry),
+        (
+            With<AttachedJointProps>,
+            With<AttachedSpringRoots>,
+            Or<(
+                Changed<SpringBoneTuning>,
+                Added<AttachedJointProps>,
+                Added<AttachedSpringRoots>,
+            )>,
+        ),
+    >,
+    children: Query<&Children>,
+    spring_roots: Query<&SpringRoot>,
+    mut joints: Query<(&Name, &mut SpringJointProps)>,
+) {
+    for (vrm_entity, tuning, registry) in vrms.iter() {
+        for spring_root in children
+            .iter_descendants(vrm_entity)
+            .filter_map(|entity| spring_roots.get(entity).ok())
+        {
+            // The props are always tuned from the VRM, so removing a tuning restores them.
+            let chain_tuning = tuning
+                .chains
+                .get(&spring_root.name)
+                .copied()
+                .unwrap_or_default();
+            for joint in spring_root.joints.iter() {
+                let Ok((name, mut props)) = joints.get_mut(*joint) else {
+                    continue;
+                };
+                let Some(original) = registry.get(name) else {
+                    continue;
+                };
+                *props = chain_tuning.apply(original);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::attach::{AttachedJointProps, AttachedSpringRoots};
+    use crate::vrm::spring_bone::registry::SpringJointPropsRegistry;
+    use crate::vrm::spring_bone::tuning::{
+        apply_spring_bone_tuning, insert_preset_tuning, SpringBonePresets, SpringBoneTuning,
+        SpringChainTuning, SpringPropsMultiplier, SpringPropsOverride,
+    };
+    use crate::vrm::spring_bone::{SpringJointProps, SpringRoot};
+    use crate::vrm::Vrm;
+    use bevy::core::Name;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::Vec3;
+    use bevy::prelude::{BuildChildren, Commands, Entity};
+    use bevy::utils::default;
+    use std::collections::BTreeMap;
+
+    fn hair_props() -> SpringJointProps {
+        SpringJointProps {
+            drag_force: 0.4,
+            gravity_power: 0.2,
+            stiffness: 1.,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_multipliers() {
+        let tuning = SpringChainTuning {
+            multiplier: SpringPropsMultiplier {
+                drag_force: 2.,
+                stiffness: 3.,
+                ..default()
+            },
+            overrides: SpringPropsOverride {
+                stiffness: Some(0.5),
+                gravity_dir: Some(Vec3::X),
+                ..default()
+            },
+        };
+        let props = tuning.apply(&hair_props());
+        assert!((props.drag_force - 0.8).abs() < 1e-5);
+        assert_eq!(props.stiffness, 0.5);
+        assert_eq!(props.gravity_dir, Vec3::X);
+        assert_eq!(props.gravity_power, 0.2);
+    }
+
+    #[test]
+    fn default_tuning_keeps_props() {
+        assert_eq!(
+            SpringChainTuning::default().apply(&hair_props()),
+            hair_props()
+        );
+    }
+
+    #[test]
+    fn tune_chain_by_spring_name() -> TestResult {
+        let mut app = test_app();
+        let (vrm, hair, skirt) = app.world_mut().run_system_once(|mut commands: Commands| {
+            let hair = commands.spawn((Name::new("hair"), hair_props())).id();
+            let skirt = commands.spawn((Name::new("skirt"), hair_props())).id();
+            commands.entity(hair).insert(SpringRoot {
+                name: "Hair".to_string(),
+                joints: vec![hair],
+                ..default()
+            });
+            commands.entity(skirt).insert(SpringRoot {
+                name: "Skirt".to_string(),
+                joints: vec![skirt],
+                ..default()
+            });
+            let mut tuning = SpringBoneTuning::default();
+            tuning.chain_mut("Hair").overrides.stiffness = Some(4.);
+            let vrm = commands
+                .spawn((
+                    tuning,
+                    SpringJointPropsRegistry(
+                        [
+                            (Name::new("hair"), hair_props()),
+                            (Name::new("skirt"), hair_props()),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    AttachedJointProps,
+                    AttachedSpringRoots,
+                ))
+                .add_children(&[hair, skirt])
+                .id();
+            (vrm, hair, skirt)
+        })?;
+        app.world_mut().run_system_once(apply_spring_bone_tuning)?;
+        assert_eq!(
+            app.world().get::<SpringJointProps>(hair).unwrap().stiffness,
+            4.
+        );
+        assert_eq!(
+            app.world().get::<SpringJointProps>(skirt).unwrap(),
+            &hair_props()
+        );
+
+        app.world_mut()
+            .get_mut::<SpringBoneTuning>(vrm)
+            .unwrap()
+            .chains
+            .clear();
+        app.world_mut().run_system_once(apply_spring_bone_tuning)?;
+        assert_eq!(
+            app.world().get::<SpringJointProps>(hair).unwrap(),
+            &hair_props()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn insert_preset_of_vrm() -> TestResult {
+        let mut app = test_app();
+        let mut tuning = SpringBoneTuning::default();
+        tuning.chain_mut("Hair").multiplier.stiffness = 2.;
+        app.insert_resource(SpringBonePresets(BTreeMap::from([(
+            "Avatar".to_string(),
+            tuning.clone(),
+        )])));
+        let (avatar, other): (Entity, Entity) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                (
+                    commands.spawn((Vrm, Name::new("Avatar"))).id(),
+                    commands.spawn((Vrm, Name::new("Other"))).id(),
+                )
+            })?;
+        app.world_mut().run_system_once(insert_preset_tuning)?;
+        assert_eq!(app.world().get::<SpringBoneTuning>(avatar), Some(&tuning));
+        assert!(app.world().get::<SpringBoneTuning>(other).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn save_and_load_presets() -> TestResult {
+        let mut tuning = SpringBoneTuning::default();
+        tuning.chain_mut("Hair").overrides.drag_force = Some(0.7);
+        tuning.chain_mut("Skirt").multiplier.gravity_power = 0.5;
+        let presets = SpringBonePresets(BTreeMap::from([("Avatar".to_string(), tuning)]));
+
+        // The path is unique to the test and the process so that parallel runs do not share the file.
+        let path = std::env::temp_dir().join(format!(
+            "bevy_vrma_save_and_load_presets_{}.json",
+            std::process::id()
+        ));
+        presets.save(&path)?;
+        let loaded = SpringBonePresets::load(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(loaded, presets);
+        Ok(())
+    }
+}
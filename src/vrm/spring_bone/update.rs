@@ -1,11 +1,23 @@
-use crate::vrm::extensions::vrmc_spring_bone::ColliderShape;
-use crate::vrm::spring_bone::{SpringJointProps, SpringJointState, SpringRoot};
+use crate::vrm::extensions::vrmc_spring_bone::{SpringColliderShape, SpringJointLimit};
+use crate::vrm::spring_bone::lod::{SpringBoneLod, SpringChainLod};
+use crate::vrm::spring_bone::solver::{
+    par_advance_chains, SpringChainLink, SpringChainSolver, SpringSolverCollider, SpringSolverJoint,
+};
+use crate::vrm::spring_bone::switch::{DisabledSpringCollider, SpringChainActivity};
+use crate::vrm::spring_bone::wind::{SpringBoneWind, WindField, WindZone};
+use crate::vrm::spring_bone::world_collider::{
+    collect_external_colliders, ExternalCollider, ShareSpringColliders, SharedSpringColliders,
+    SpringCollider,
+};
+use crate::vrm::spring_bone::{SpringBoneClock, SpringJointProps, SpringJointState, SpringRoot};
 use bevy::app::{App, PostUpdate};
-use bevy::math::Vec3;
+use bevy::ecs::change_detection::DetectChanges;
+use bevy::math::Mat4;
 use bevy::prelude::{
-    Entity, GlobalTransform, Parent, Plugin, Quat, Query, Res, Transform, Without,
+    Entity, GlobalTransform, Local, Parent, Plugin, Query, Res, ResMut, Transform, Without,
 };
-use bevy::time::Time;
+use bevy::time::{Time, Virtual};
+use bevy::utils::HashMap;
 
 pub struct SpringBoneUpdatePlugin;
 
@@ -18,88 +30,959 @@ impl Plugin for SpringBoneUpdatePlugin {
     }
 }
 
-fn update_spring_bones(
+/// The buffers of [`update_spring_bones`] reused across frames to avoid allocations.
+#[derive(Default)]
+pub(super) struct SpringBoneBuffers {
+    solvers: Vec<SpringChainSolver>,
+    /// The root entity and the gathered joints of each solved chain.
+    gathered: Vec<(Entity, Vec<Entity>)>,
+    /// Whether the solved chains can start a batch solved in parallel.
+    splittable: Vec<bool>,
+    /// The winds and the zones that blow them, or `None` for the global wind.
+    wind: Vec<(WindField, Option<Entity>)>,
+    external_colliders: Vec<ExternalCollider>,
+    /// The spring roots keyed by the top chain of their branches and the depth in the branches.
+    order: Vec<(Entity, usize, Entity)>,
+    /// The spring roots keyed by their joints.
+    joint_roots: HashMap<Entity, Entity>,
+    /// The spring roots keyed by the spring roots of the branches starting under their joints.
+    chain_parents: HashMap<Entity, Entity>,
+    /// The indices of the solved chain and the joint keyed by the gathered joints.
+    joint_chains: HashMap<Entity, (usize, usize)>,
+}
+
+pub(super) fn update_spring_bones(
+    mut clock: ResMut<SpringBoneClock>,
+    mut buffers: Local<SpringBoneBuffers>,
     mut transforms: Query<(&mut Transform, &mut GlobalTransform)>,
-    mut joints: Query<(&Parent, &mut SpringJointState, &SpringJointProps), Without<SpringRoot>>,
-    spring_roots: Query<&SpringRoot>,
-    colliders: Query<&ColliderShape>,
-    time: Res<Time>,
+    mut joints: Query<(
+        &Parent,
+        &mut SpringJointState,
+        &SpringJointProps,
+        Option<&SpringJointLimit>,
+    )>,
+    mut spring_roots: Query<(
+        Entity,
+        &SpringRoot,
+        &mut SpringChainActivity,
+        &mut SpringChainLod,
+    )>,
+    colliders: Query<&SpringColliderShape, Without<DisabledSpringCollider>>,
+    wind_zones: Query<(Entity, &WindZone)>,
+    world_colliders: Query<(Entity, &SpringCollider)>,
+    sharing_vrms: Query<(Entity, &ShareSpringColliders, &SharedSpringColliders)>,
+    global_wind: Res<SpringBoneWind>,
+    lod_settings: Res<SpringBoneLod>,
+    time: Res<Time<Virtual>>,
 ) {
-    let delta_time = time.delta_secs();
-    for spring_root in spring_roots.iter() {
-        for joint in spring_root.joints.iter().copied() {
-            let Ok((parent, mut state, props)) = joints.get_mut(joint) else {
-                continue;
-            };
-            let parent_gtf = transforms
-                .get(parent.get())
-                .map(|(_, gtf)| *gtf)
-                .unwrap_or_default();
-            let Ok(joint_global_pos) = transforms.get(joint).map(|(_, gtf)| gtf.translation())
-            else {
-                continue;
-            };
-
-            let inertia = (state.current_tail - state.prev_tail) * (1. - props.drag_force);
-            let stiffness = delta_time
-                * (parent_gtf.rotation()
-                    * state.initial_local_rotation
-                    * state.bone_axis
-                    * props.stiffness);
-            let external = delta_time * props.gravity_dir * props.gravity_power;
-
-            let next_tail = state.current_tail + inertia + stiffness + external;
-            let mut next_tail =
-                joint_global_pos + (next_tail - joint_global_pos).normalize() * state.bone_length;
-
-            state.prev_tail = state.current_tail;
-            state.current_tail = next_tail;
-
-            collision(
-                &mut next_tail,
-                spring_root.colliders.iter().copied(),
-                joint,
-                &transforms,
-                &colliders,
-            );
+    let SpringBoneBuffers {
+        solvers,
+        gathered,
+        splittable,
+        wind,
+        external_colliders,
+        order,
+        joint_roots,
+        chain_parents,
+        joint_chains,
+    } = &mut *buffers;
+    let elapsed = clock.elapsed().as_secs_f32();
+    let steps = clock.advance(time.delta());
+    wind.clear();
+    if global_wind.strength != 0. {
+        wind.push((WindField::from_global(&global_wind), None));
+    }
+    // The global transforms of the zones are read through `transforms` to avoid conflicting accesses.
+    wind.extend(wind_zones.iter().filter_map(|(entity, zone)| {
+        let (_, zone_gtf) = transforms.get(entity).ok()?;
+        Some((WindField::from_zone(zone, zone_gtf), Some(entity)))
+    }));
+    collect_external_colliders(
+        external_colliders,
+        &world_colliders,
+        &sharing_vrms,
+        &colliders,
+        |entity| transforms.get(entity).ok().map(|(_, gtf)| *gtf),
+    );
+    order_spring_chains(
+        order,
+        joint_roots,
+        chain_parents,
+        || {
+            spring_roots
+                .iter()
+                .map(|(root, spring_root, ..)| (root, spring_root))
+        },
+        |joint| joints.get(joint).ok().map(|(parent, ..)| parent.get()),
+    );
+    joint_chains.clear();
+    let mut chains = 0;
+    for (_, _, root) in order.iter().copied() {
+        let Ok((root, spring_root, mut activity, mut lod)) = spring_roots.get_mut(root) else {
+            continue;
+        };
+        if activity.paused {
+            continue;
+        }
+        let was_sleeping = activity.is_sleeping();
+        activity.blend(time.delta());
+        if activity.is_sleeping() && was_sleeping {
+            continue;
+        }
+        let Some((time_scale, resumed)) = lod.schedule(steps) else {
+            continue;
+        };
+        // The buffers are reused across frames to avoid allocations.
+        if solvers.len() <= chains {
+            solvers.push(SpringChainSolver::default());
+            gathered.push((Entity::PLACEHOLDER, Vec::new()));
+        }
+        let solver = &mut solvers[chains];
+        let animated = gather_spring_chain(
+            solver,
+            &mut gathered[chains].1,
+            (chains, joint_chains),
+            spring_root,
+            &mut transforms,
+            &joints,
+            &colliders,
+        );
+        solver.colliders.extend(
+            external_colliders
+                .iter()
+                .filter(|external| external.affects(spring_root))
+                .map(|external| external.collider),
+        );
+        if !lod.wake(solver, animated) {
+            continue;
+        }
+        solver.wind.extend(
+            wind.iter()
+                .filter(|(_, zone)| {
+                    zone.is_none_or(|zone| {
+                        wind_zones
+                            .get(zone)
+                            .is_ok_and(|(_, zone)| zone.affects(spring_root))
+                    })
+                })
+                .map(|(field, _)| *field),
+        );
+        solver.elapsed = elapsed;
+        solver.weight = activity.weight();
+        solver.time_scale = time_scale;
+        if was_sleeping || resumed {
+            // The tails have been left behind while sleeping or culled, so they restart from the animated pose.
+            solver.reset_tails();
+        }
+        gathered[chains].0 = root;
+        for (index, joint) in gathered[chains].1.iter().enumerate() {
+            joint_chains.insert(*joint, (chains, index));
+        }
+        chains += 1;
+    }
 
-            let to = (parent_gtf.compute_matrix() * state.initial_local_matrix)
-                .inverse()
-                .transform_point3(next_tail)
-                .normalize();
+    // Chains never share joints, and the branches are solved after their parent chains in the same batch.
+    par_advance_chains(
+        &mut solvers[..chains],
+        splittable,
+        steps,
+        clock.timestep.as_secs_f32(),
+        clock.interpolate.then(|| clock.overstep_fraction()),
+    );
 
-            let Ok((mut tf, mut gtf)) = transforms.get_mut(joint) else {
-                continue;
-            };
+    for (solver, (_, gathered)) in solvers[..chains].iter().zip(gathered.iter()) {
+        scatter_spring_chain(solver, gathered, &mut transforms, &mut joints);
+    }
 
-            tf.rotation =
-                state.initial_local_rotation * Quat::from_rotation_arc(state.bone_axis, to);
-            *gtf = parent_gtf.mul_transform(*tf);
+    let delta_time = clock.timestep.as_secs_f32();
+    for (solver, (root, _)) in solvers[..chains].iter().zip(gathered.iter()) {
+        if let Ok((_, _, _, mut lod)) = spring_roots.get_mut(*root) {
+            lod.settle(
+                solver,
+                solver.tail_speed(delta_time),
+                time.delta(),
+                &lod_settings,
+            );
         }
     }
 }
 
-fn collision(
-    next_tail: &mut Vec3,
-    collider_entities: impl Iterator<Item = Entity>,
-    joint_entity: Entity,
-    transforms: &Query<(&mut Transform, &mut GlobalTransform)>,
-    colliders: &Query<&ColliderShape>,
+/// Orders the spring roots so that the branches follow the chains their first joints hang from,
+/// keeping the branches of the same top chain together.
+fn order_spring_chains<'a, I: Iterator<Item = (Entity, &'a SpringRoot)>>(
+    order: &mut Vec<(Entity, usize, Entity)>,
+    joint_roots: &mut HashMap<Entity, Entity>,
+    chain_parents: &mut HashMap<Entity, Entity>,
+    spring_roots: impl Fn() -> I,
+    parent_of: impl Fn(Entity) -> Option<Entity>,
 ) {
-    let Ok(joint_shape) = colliders.get(joint_entity) else {
-        return;
-    };
-    for collider in collider_entities {
-        let Ok(collider_shape) = colliders.get(collider) else {
+    order.clear();
+    joint_roots.clear();
+    chain_parents.clear();
+    for (root, spring_root) in spring_roots() {
+        for joint in spring_root.joints.iter() {
+            joint_roots.insert(*joint, root);
+        }
+    }
+    for (root, spring_root) in spring_roots() {
+        let parent_root = spring_root
+            .joints
+            .first()
+            .and_then(|first| parent_of(*first))
+            .and_then(|parent| joint_roots.get(&parent));
+        if let Some(parent_root) = parent_root.filter(|parent_root| **parent_root != root) {
+            chain_parents.insert(root, *parent_root);
+        }
+    }
+    for (root, _) in spring_roots() {
+        let (mut top, mut depth) = (root, 0);
+        // The depth is bounded in case the joints are shared by chains in a loop.
+        while let Some(parent) = chain_parents
+            .get(&top)
+            .filter(|_| depth < chain_parents.len())
+        {
+            top = *parent;
+            depth += 1;
+        }
+        order.push((top, depth, root));
+    }
+    order.sort_unstable();
+}
+
+/// Copies the chain into `solver`, and records the entities of the gathered joints into `gathered`.
+///
+/// The joints hanging from the joints of the preceding chains in `joint_chains` are linked to them.
+/// If the rotation of a joint has been changed since the last update by other than the spring bone,
+/// it is recorded as the animated rotation, and `true` is returned.
+fn gather_spring_chain(
+    solver: &mut SpringChainSolver,
+    gathered: &mut Vec<Entity>,
+    (chain, joint_chains): (usize, &HashMap<Entity, (usize, usize)>),
+    spring_root: &SpringRoot,
+    transforms: &mut Query<(&mut Transform, &mut GlobalTransform)>,
+    joints: &Query<(
+        &Parent,
+        &mut SpringJointState,
+        &SpringJointProps,
+        Option<&SpringJointLimit>,
+    )>,
+    colliders: &Query<&SpringColliderShape, Without<DisabledSpringCollider>>,
+) -> bool {
+    solver.clear();
+    gathered.clear();
+    let mut animated = false;
+    solver.center = center_matrix(spring_root, transforms);
+    for joint in spring_root.joints.iter().copied() {
+        let Ok((parent, state, props, limit)) = joints.get(joint) else {
             continue;
         };
-        let Ok((_, collider_gtf)) = transforms.get(collider) else {
+        let Ok((tf, gtf)) = transforms.get_mut(joint) else {
             continue;
         };
-        let (dir, distance) =
-            collider_shape.calc_collision(*next_tail, collider_gtf, joint_shape.radius());
-        if distance < 0. {
-            *next_tail += dir * distance;
+        let mut state = *state;
+        if tf.is_changed() {
+            state.animated_rotation = tf.rotation;
+            animated = true;
+        }
+        let (tf, gtf) = (*tf, *gtf);
+        if let Some((parent_chain, parent_joint)) = joint_chains.get(&parent.get()) {
+            solver.links.push(SpringChainLink {
+                joint: solver.joints.len(),
+                chains_back: chain - parent_chain,
+                parent_joint: *parent_joint,
+            });
         }
+        let parent = (gathered.last() != Some(&parent.get())).then(|| {
+            transforms
+                .get(parent.get())
+                .map(|(_, gtf)| *gtf)
+                .unwrap_or_default()
+        });
+        solver.joints.push(SpringSolverJoint {
+            state,
+            props: *props,
+            transform: tf,
+            parent,
+            global: gtf,
+            limit: limit.copied(),
+        });
+        gathered.push(joint);
+    }
+    for collider in spring_root.colliders.iter().copied() {
+        let Ok(shape) = colliders.get(collider) else {
+            continue;
+        };
+        let Ok((_, gtf)) = transforms.get(collider) else {
+            continue;
+        };
+        solver.colliders.push(SpringSolverCollider {
+            shape: *shape,
+            transform: *gtf,
+        });
+    }
+    animated
+}
+
+/// Writes the results of `solver` back to the joints.
+fn scatter_spring_chain(
+    solver: &SpringChainSolver,
+    gathered: &[Entity],
+    transforms: &mut Query<(&mut Transform, &mut GlobalTransform)>,
+    joints: &mut Query<(
+        &Parent,
+        &mut SpringJointState,
+        &SpringJointProps,
+        Option<&SpringJointLimit>,
+    )>,
+) {
+    for (entity, joint) in gathered.iter().zip(solver.joints.iter()) {
+        if let Ok((_, mut state, ..)) = joints.get_mut(*entity) {
+            *state = joint.state;
+        }
+        if let Ok((mut tf, mut gtf)) = transforms.get_mut(*entity) {
+            tf.rotation = joint.transform.rotation;
+            *gtf = joint.global;
+        }
+    }
+}
+
+pub(super) fn center_matrix(
+    spring_root: &SpringRoot,
+    transforms: &Query<(&mut Transform, &mut GlobalTransform)>,
+) -> Mat4 {
+    spring_root
+        .center_node
+        .and_then(|center| transforms.get(center).ok())
+        .map(|(_, center_gtf)| center_gtf.compute_matrix())
+        .unwrap_or(Mat4::IDENTITY)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::extensions::vrmc_spring_bone::{Sphere, SpringColliderShape};
+    use crate::vrm::spring_bone::lod::{SpringBoneLod, SpringChainLod};
+    use crate::vrm::spring_bone::switch::SpringChainActivity;
+    use crate::vrm::spring_bone::update::update_spring_bones;
+    use crate::vrm::spring_bone::wind::{SpringBoneWind, WindZone};
+    use crate::vrm::spring_bone::world_collider::{
+        cache_shared_colliders, ShareSpringColliders, SpringCollider, SpringColliderTargets,
+    };
+    use crate::vrm::spring_bone::{
+        SpringBoneClock, SpringJointProps, SpringJointState, SpringRoot,
+    };
+    use bevy::app::{App, PostUpdate};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::{Quat, Vec3};
+    use bevy::prelude::{
+        BuildChildren, Commands, Entity, GlobalTransform, IntoSystemConfigs, Transform,
+    };
+    use bevy::time::{Time, TimeUpdateStrategy, Virtual};
+    use bevy::utils::default;
+    use std::time::Duration;
+
+    pub(crate) fn step(
+        app: &mut App,
+        delta: Duration,
+    ) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(delta));
+        app.update();
+    }
+
+    pub(crate) fn spring_app() -> App {
+        let mut app = test_app();
+        app.insert_resource(SpringBoneClock {
+            interpolate: false,
+            ..SpringBoneClock::from_hz(60.)
+        })
+        .init_resource::<SpringBoneWind>()
+        .init_resource::<SpringBoneLod>()
+        .add_systems(
+            PostUpdate,
+            (cache_shared_colliders, update_spring_bones).chain(),
+        );
+        // The first update only records the start time.
+        step(&mut app, Duration::ZERO);
+        app
+    }
+
+    /// Spawns `center -> root -> joint -> tail` hanging down, where the center has already moved to `x = 10`,
+    /// and returns the entity of the joint.
+    fn spawn_moved_chain(
+        app: &mut App,
+        use_center: bool,
+    ) -> TestResult<Entity> {
+        let joint = app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                let center = commands
+                    .spawn((Transform::default(), GlobalTransform::from_xyz(10., 0., 0.)))
+                    .id();
+                let root = commands
+                    .spawn((Transform::default(), GlobalTransform::from_xyz(10., 0., 0.)))
+                    .set_parent(center)
+                    .id();
+                let joint = commands
+                    .spawn((
+                        Transform::default(),
+                        GlobalTransform::from_xyz(10., 0., 0.),
+                        // Only the inertia moves the tail.
+                        SpringJointProps {
+                            drag_force: 0.,
+                            stiffness: 0.,
+                            ..default()
+                        },
+                        SpringJointState {
+                            // Before moving, the tail was at `(0, -1, 0)` in the both of world and center space.
+                            prev_tail: Vec3::NEG_Y,
+                            current_tail: Vec3::NEG_Y,
+                            bone_axis: Vec3::NEG_Y,
+                            bone_length: 1.,
+                            ..default()
+                        },
+                    ))
+                    .set_parent(root)
+                    .id();
+                let tail = commands
+                    .spawn((
+                        Transform::from_xyz(0., -1., 0.),
+                        GlobalTransform::from_xyz(10., -1., 0.),
+                    ))
+                    .set_parent(joint)
+                    .id();
+                commands.entity(root).insert(SpringRoot {
+                    joints: vec![root, joint, tail],
+                    colliders: vec![],
+                    center_node: use_center.then_some(center),
+                    ..default()
+                });
+                joint
+            })?;
+        Ok(joint)
+    }
+
+    #[test]
+    fn moving_center_does_not_inject_inertia() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_moved_chain(&mut app, true)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let tf = app.world().get::<Transform>(joint).unwrap();
+        assert!(tf.rotation.abs_diff_eq(Quat::IDENTITY, 1e-5));
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn moving_without_center_injects_inertia() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_moved_chain(&mut app, false)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let tf = app.world().get::<Transform>(joint).unwrap();
+        assert!(!tf.rotation.abs_diff_eq(Quat::IDENTITY, 1e-3));
+        Ok(())
+    }
+
+    #[test]
+    fn same_result_regardless_of_frame_rate() -> TestResult {
+        let mut app_30fps = spring_app();
+        let joint_30fps = spawn_moved_chain(&mut app_30fps, false)?;
+        for _ in 0..3 {
+            step(&mut app_30fps, Duration::from_secs_f64(1. / 30.));
+        }
+
+        let mut app_60fps = spring_app();
+        let joint_60fps = spawn_moved_chain(&mut app_60fps, false)?;
+        for _ in 0..6 {
+            step(&mut app_60fps, Duration::from_secs_f64(1. / 60.));
+        }
+
+        let state_30fps = app_30fps
+            .world()
+            .get::<SpringJointState>(joint_30fps)
+            .unwrap();
+        let state_60fps = app_60fps
+            .world()
+            .get::<SpringJointState>(joint_60fps)
+            .unwrap();
+        assert!(state_30fps
+            .current_tail
+            .abs_diff_eq(state_60fps.current_tail, 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn paused_time_does_not_simulate() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_moved_chain(&mut app, false)?;
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        step(&mut app, Duration::from_secs(1));
+
+        let tf = app.world().get::<Transform>(joint).unwrap();
+        assert_eq!(tf.rotation, Quat::IDENTITY);
+        Ok(())
+    }
+
+    /// Spawns `root -> joint -> tail` hanging down from the origin and a sphere collider at `(sphere_x, -1, 0)`,
+    /// and returns the entity of the joint.
+    fn spawn_chain_with_sphere(
+        app: &mut App,
+        hit_radius: f32,
+        sphere_x: f32,
+    ) -> TestResult<Entity> {
+        let joint = app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                let root = commands
+                    .spawn((Transform::default(), GlobalTransform::default()))
+                    .id();
+                let joint = commands
+                    .spawn((
+                        Transform::default(),
+                        GlobalTransform::default(),
+                        SpringJointProps {
+                            drag_force: 0.,
+                            stiffness: 0.,
+                            hit_radius,
+                            ..default()
+                        },
+                        SpringJointState {
+                            prev_tail: Vec3::NEG_Y,
+                            current_tail: Vec3::NEG_Y,
+                            bone_axis: Vec3::NEG_Y,
+                            bone_length: 1.,
+                            ..default()
+                        },
+                    ))
+                    .set_parent(root)
+                    .id();
+                let tail = commands
+                    .spawn((
+                        Transform::from_xyz(0., -1., 0.),
+                        GlobalTransform::from_xyz(0., -1., 0.),
+                    ))
+                    .set_parent(joint)
+                    .id();
+                let collider = commands
+                    .spawn((
+                        Transform::from_xyz(sphere_x, -1., 0.),
+                        GlobalTransform::from_xyz(sphere_x, -1., 0.),
+                        SpringColliderShape::Sphere(Sphere {
+                            offset: [0., 0., 0.],
+                            radius: 0.5,
+                        }),
+                    ))
+                    .set_parent(root)
+                    .id();
+                commands.entity(root).insert(SpringRoot {
+                    joints: vec![root, joint, tail],
+                    colliders: vec![collider],
+                    ..default()
+                });
+                joint
+            })?;
+        Ok(joint)
+    }
+
+    #[test]
+    fn push_tail_out_of_sphere() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0.1, 0.3)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        // The tail is pushed to the opposite side of the sphere, keeping the bone length.
+        assert!(state.current_tail.x < -0.2);
+        assert!((state.current_tail.length() - 1.).abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn collide_with_hit_radius() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0., 0.55)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0.1, 0.55)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.x < 0.);
+        Ok(())
+    }
+
+    /// Spawns a sphere at `x` that only collides as a collider outside of the chain.
+    fn spawn_world_sphere(
+        app: &mut App,
+        x: f32,
+        targets: SpringColliderTargets,
+    ) -> TestResult {
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands| {
+                commands.spawn((
+                    GlobalTransform::from_xyz(x, -1., 0.),
+                    SpringCollider {
+                        shape: SpringColliderShape::Sphere(Sphere {
+                            offset: [0., 0., 0.],
+                            radius: 0.5,
+                        }),
+                        targets: targets.clone(),
+                        ..default()
+                    },
+                ));
+            })?;
+        Ok(())
+    }
+
+    #[test]
+    fn collide_with_world_collider() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0.1, 10.)?;
+        spawn_world_sphere(&mut app, 0.3, SpringColliderTargets::All)?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.x < -0.2);
+
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0.1, 10.)?;
+        spawn_world_sphere(
+            &mut app,
+            0.3,
+            SpringColliderTargets::Vrms(vec![Entity::PLACEHOLDER]),
+        )?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn collide_with_colliders_shared_by_other_vrm() -> TestResult {
+        let mut app = spring_app();
+        let joint = spawn_chain_with_sphere(&mut app, 0.1, 10.)?;
+        app.world_mut().run_system_once(|mut commands: Commands| {
+            commands
+                .spawn((GlobalTransform::default(), ShareSpringColliders::default()))
+                .with_child((
+                    Transform::from_xyz(0.3, -1., 0.),
+                    GlobalTransform::from_xyz(0.3, -1., 0.),
+                    SpringColliderShape::Sphere(Sphere {
+                        offset: [0., 0., 0.],
+                        radius: 0.5,
+                    }),
+                ));
+        })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.x < -0.2);
+        Ok(())
+    }
+
+    #[test]
+    fn simulate_single_joint_chain() -> TestResult {
+        let mut app = spring_app();
+        let joint = app.world_mut().run_system_once(|mut commands: Commands| {
+            let parent = commands
+                .spawn((Transform::default(), GlobalTransform::default()))
+                .id();
+            let joint = commands
+                .spawn((
+                    Transform::default(),
+                    GlobalTransform::default(),
+                    SpringJointProps {
+                        stiffness: 0.,
+                        gravity_dir: Vec3::NEG_Y,
+                        gravity_power: 1.,
+                        ..default()
+                    },
+                    SpringJointState {
+                        prev_tail: Vec3::X * 0.07,
+                        current_tail: Vec3::X * 0.07,
+                        bone_axis: Vec3::X,
+                        bone_length: 0.07,
+                        ..default()
+                    },
+                ))
+                .set_parent(parent)
+                .id();
+            commands.entity(joint).insert(SpringRoot {
+                joints: vec![joint],
+                ..default()
+            });
+            joint
+        })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.y < 0.);
+        let tf = app.world().get::<Transform>(joint).unwrap();
+        assert_ne!(tf.rotation, Quat::IDENTITY);
+        Ok(())
+    }
+
+    #[test]
+    fn resting_chain_sleeps_until_root_moves() -> TestResult {
+        let mut app = spring_app();
+        app.insert_resource(SpringBoneLod {
+            sleep_delay: Duration::from_millis(100),
+            ..default()
+        });
+        let (parent, joint) = app.world_mut().run_system_once(|mut commands: Commands| {
+            let parent = commands
+                .spawn((Transform::default(), GlobalTransform::default()))
+                .id();
+            let joint = commands
+                .spawn((
+                    Transform::default(),
+                    GlobalTransform::default(),
+                    SpringJointProps::default(),
+                    SpringJointState {
+                        prev_tail: Vec3::NEG_Y,
+                        current_tail: Vec3::NEG_Y,
+                        bone_axis: Vec3::NEG_Y,
+                        bone_length: 1.,
+                        ..default()
+                    },
+                ))
+                .set_parent(parent)
+                .id();
+            commands.entity(joint).insert(SpringRoot {
+                joints: vec![joint],
+                ..default()
+            });
+            (parent, joint)
+        })?;
+        for _ in 0..10 {
+            step(&mut app, Duration::from_secs_f64(1. / 60.));
+        }
+        assert!(app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+
+        app.world_mut().entity_mut(parent).insert((
+            Transform::from_xyz(1., 0., 0.),
+            GlobalTransform::from_xyz(1., 0., 0.),
+        ));
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        assert!(!app
+            .world()
+            .get::<SpringChainLod>(joint)
+            .unwrap()
+            .is_asleep());
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(state.current_tail.x < 1.);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_chain_returns_to_animated_pose() -> TestResult {
+        let mut app = spring_app();
+        let joint = app.world_mut().run_system_once(|mut commands: Commands| {
+            let parent = commands
+                .spawn((Transform::default(), GlobalTransform::default()))
+                .id();
+            let joint = commands
+                .spawn((
+                    Transform::default(),
+                    GlobalTransform::default(),
+                    SpringJointProps {
+                        stiffness: 0.,
+                        gravity_dir: Vec3::X,
+                        gravity_power: 1.,
+                        ..default()
+                    },
+                    SpringJointState {
+                        prev_tail: Vec3::NEG_Y,
+                        current_tail: Vec3::NEG_Y,
+                        bone_axis: Vec3::NEG_Y,
+                        bone_length: 1.,
+                        ..default()
+                    },
+                ))
+                .set_parent(parent)
+                .id();
+            commands.entity(joint).insert(SpringRoot {
+                joints: vec![joint],
+                ..default()
+            });
+            joint
+        })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        assert_ne!(
+            app.world().get::<Transform>(joint).unwrap().rotation,
+            Quat::IDENTITY
+        );
+
+        let mut activity = app
+            .world_mut()
+            .get_mut::<SpringChainActivity>(joint)
+            .unwrap();
+        activity.enabled = false;
+        activity.blend_duration = Duration::ZERO;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        assert_eq!(
+            app.world().get::<Transform>(joint).unwrap().rotation,
+            Quat::IDENTITY
+        );
+
+        let state = *app.world().get::<SpringJointState>(joint).unwrap();
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+        assert_eq!(app.world().get::<SpringJointState>(joint), Some(&state));
+        Ok(())
+    }
+
+    #[test]
+    fn branch_follows_solved_parent_chain() -> TestResult {
+        let mut app = spring_app();
+        let (parent_joint, branch_joint) =
+            app.world_mut().run_system_once(|mut commands: Commands| {
+                let joint = |translation: Vec3| {
+                    (
+                        Transform::from_translation(translation),
+                        GlobalTransform::from_translation(translation),
+                        SpringJointProps {
+                            stiffness: 0.,
+                            gravity_dir: Vec3::X,
+                            gravity_power: 1.,
+                            ..default()
+                        },
+                        SpringJointState {
+                            prev_tail: translation + Vec3::NEG_Y,
+                            current_tail: translation + Vec3::NEG_Y,
+                            bone_axis: Vec3::NEG_Y,
+                            bone_length: 1.,
+                            ..default()
+                        },
+                    )
+                };
+                let root = commands
+                    .spawn((Transform::default(), GlobalTransform::default()))
+                    .id();
+                let parent_joint = commands.spawn(joint(Vec3::ZERO)).set_parent(root).id();
+                let branch_joint = commands
+                    .spawn(joint(Vec3::new(0., -0.5, 0.)))
+                    .set_parent(parent_joint)
+                    .id();
+                // The branch is spawned first so that it would be gathered first without ordering.
+                commands.entity(branch_joint).insert(SpringRoot {
+                    joints: vec![branch_joint],
+                    ..default()
+                });
+                commands.entity(parent_joint).insert(SpringRoot {
+                    joints: vec![parent_joint],
+                    ..default()
+                });
+                (parent_joint, branch_joint)
+            })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let parent_gtf = app.world().get::<GlobalTransform>(parent_joint).unwrap();
+        assert_ne!(parent_gtf.rotation(), Quat::IDENTITY);
+        let branch_gtf = app.world().get::<GlobalTransform>(branch_joint).unwrap();
+        assert!(branch_gtf
+            .translation()
+            .abs_diff_eq(parent_gtf.transform_point(Vec3::new(0., -0.5, 0.)), 1e-5));
+        Ok(())
+    }
+
+    #[test]
+    fn wind_zone_blows_chain() -> TestResult {
+        let mut app = spring_app();
+        let joint = app.world_mut().run_system_once(|mut commands: Commands| {
+            commands.spawn((
+                WindZone::default(),
+                GlobalTransform::from(Transform::default().looking_to(Vec3::X, Vec3::Y)),
+            ));
+            let parent = commands
+                .spawn((Transform::default(), GlobalTransform::default()))
+                .id();
+            let joint = commands
+                .spawn((
+                    Transform::default(),
+                    GlobalTransform::default(),
+                    SpringJointProps {
+                        stiffness: 0.,
+                        ..default()
+                    },
+                    SpringJointState {
+                        prev_tail: Vec3::NEG_Y,
+                        current_tail: Vec3::NEG_Y,
+                        bone_axis: Vec3::NEG_Y,
+                        bone_length: 1.,
+                        ..default()
+                    },
+                ))
+                .set_parent(parent)
+                .id();
+            commands.entity(joint).insert(SpringRoot {
+                joints: vec![joint],
+                ..default()
+            });
+            joint
+        })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let state = app.world().get::<SpringJointState>(joint).unwrap();
+        assert!(0. < state.current_tail.x);
+        assert!(state.current_tail.z.abs() < 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn wind_zone_blows_only_listed_springs() -> TestResult {
+        let mut app = spring_app();
+        let (hair, skirt) = app.world_mut().run_system_once(|mut commands: Commands| {
+            commands.spawn((
+                WindZone {
+                    springs: vec!["Hair".to_string()],
+                    ..default()
+                },
+                GlobalTransform::from(Transform::default().looking_to(Vec3::X, Vec3::Y)),
+            ));
+            let mut spawn_chain = |name: &str| {
+                let parent = commands
+                    .spawn((Transform::default(), GlobalTransform::default()))
+                    .id();
+                let joint = commands
+                    .spawn((
+                        Transform::default(),
+                        GlobalTransform::default(),
+                        SpringJointProps {
+                            stiffness: 0.,
+                            ..default()
+                        },
+                        SpringJointState {
+                            prev_tail: Vec3::NEG_Y,
+                            current_tail: Vec3::NEG_Y,
+                            bone_axis: Vec3::NEG_Y,
+                            bone_length: 1.,
+                            ..default()
+                        },
+                    ))
+                    .set_parent(parent)
+                    .id();
+                commands.entity(joint).insert(SpringRoot {
+                    name: name.to_string(),
+                    joints: vec![joint],
+                    ..default()
+                });
+                joint
+            };
+            (spawn_chain("Hair"), spawn_chain("Skirt"))
+        })?;
+        step(&mut app, Duration::from_secs_f64(1. / 60.));
+
+        let hair = app.world().get::<SpringJointState>(hair).unwrap();
+        assert!(0. < hair.current_tail.x);
+        let skirt = app.world().get::<SpringJointState>(skirt).unwrap();
+        assert!(skirt.current_tail.abs_diff_eq(Vec3::NEG_Y, 1e-5));
+        Ok(())
     }
 }
@@ -0,0 +1,345 @@
+use crate::vrm::spring_bone::world_collider::SpringColliderTargets;
+use crate::vrm::spring_bone::SpringRoot;
+use bevy::app::App;
+use bevy::math::Vec3;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SpringBoneWindPlugin;
+
+impl Plugin for SpringBoneWindPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringBoneWind>()
+            .register_type::<WindZone>()
+            .register_type::<WindZoneMode>()
+            .register_type::<Turbulence>()
+            .init_resource::<SpringBoneWind>();
+    }
+}
+
+/// The wind that blows on all spring bones.
+///
+/// The force is added to the external force of each joint in addition to the gravity.
+#[derive(Resource, Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Resource, Serialize, Deserialize, Default)]
+pub struct SpringBoneWind {
+    /// The direction of the wind in the world space.
+    pub direction: Vec3,
+    pub strength: f32,
+    pub turbulence: Turbulence,
+}
+
+impl Default for SpringBoneWind {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::NEG_Z,
+            strength: 0.,
+            turbulence: Turbulence::default(),
+        }
+    }
+}
+
+/// The wind that blows on spring bones from the entity.
+///
+/// The direction and the position of the wind follow the [`GlobalTransform`] of the entity.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+#[require(Transform)]
+pub struct WindZone {
+    pub mode: WindZoneMode,
+    pub strength: f32,
+    pub turbulence: Turbulence,
+
+    /// The VRMs whose spring chains the wind blows on.
+    #[serde(default)]
+    pub targets: SpringColliderTargets,
+
+    /// If not empty, the wind only blows on the springs of the listed names.
+    #[serde(default)]
+    pub springs: Vec<String>,
+}
+
+impl Default for WindZone {
+    fn default() -> Self {
+        Self {
+            mode: WindZoneMode::Directional,
+            strength: 1.,
+            turbulence: Turbulence::default(),
+            targets: SpringColliderTargets::All,
+            springs: Vec::new(),
+        }
+    }
+}
+
+impl WindZone {
+    /// Returns `true` if the wind blows on the spring chain.
+    pub fn affects(
+        &self,
+        spring_root: &SpringRoot,
+    ) -> bool {
+        self.targets.contains(spring_root.vrm)
+            && (self.springs.is_empty() || self.springs.contains(&spring_root.name))
+    }
+}
+
+#[derive(Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize)]
+pub enum WindZoneMode {
+    /// The wind blows toward the forward direction of the entity everywhere.
+    Directional,
+
+    /// The wind blows outward from the entity, and weakens linearly to zero at `radius`.
+    Spherical { radius: f32 },
+}
+
+/// The noise that varies the wind over time.
+///
+/// The noise is deterministic, so the same seed always produces the same wind.
+#[derive(Reflect, Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+pub struct Turbulence {
+    /// The amplitude of the noise relative to the strength of the wind.
+    pub amplitude: f32,
+
+    /// How many times the noise changes per second.
+    pub frequency: f32,
+
+    pub seed: u32,
+}
+
+impl Default for Turbulence {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.,
+            frequency: 1.,
+            seed: 0,
+        }
+    }
+}
+
+/// The wind evaluated by the spring chain solver.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindField {
+    pub shape: WindFieldShape,
+    pub strength: f32,
+    pub turbulence: Turbulence,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WindFieldShape {
+    Directional { direction: Vec3 },
+    Spherical { center: Vec3, radius: f32 },
+}
+
+impl WindField {
+    pub fn from_global(wind: &SpringBoneWind) -> Self {
+        Self {
+            shape: WindFieldShape::Directional {
+                direction: wind.direction,
+            },
+            strength: wind.strength,
+            turbulence: wind.turbulence,
+        }
+    }
+
+    pub fn from_zone(
+        zone: &WindZone,
+        zone_gtf: &GlobalTransform,
+    ) -> Self {
+        let shape = match zone.mode {
+            WindZoneMode::Directional => WindFieldShape::Directional {
+                direction: *zone_gtf.forward(),
+            },
+            WindZoneMode::Spherical { radius } => WindFieldShape::Spherical {
+                center: zone_gtf.translation(),
+                radius,
+            },
+        };
+        Self {
+            shape,
+            strength: zone.strength,
+            turbulence: zone.turbulence,
+        }
+    }
+
+    /// Returns the force of the wind at `position` in the world space at `time` seconds.
+    pub fn force_at(
+        &self,
+        position: Vec3,
+        time: f32,
+    ) -> Vec3 {
+        let (direction, strength) = match self.shape {
+            WindFieldShape::Directional { direction } => {
+                (direction.normalize_or_zero(), self.strength)
+            }
+            WindFieldShape::Spherical { center, radius } => {
+                let offset = position - center;
+                let distance = offset.length();
+                if radius <= distance {
+                    return Vec3::ZERO;
+                }
+                (
+                    offset.normalize_or_zero(),
+                    self.strength * (1. - distance / radius),
+                )
+            }
+        };
+        let turbulence = self.turbulence.sample(position, time);
+        (direction + turbulence) * strength
+    }
+}
+
+impl Turbulence {
+    /// Samples the noise at `position` at `time` seconds.
+    ///
+    /// The position shifts the phase of the noise, so nearby strands of hair sway slightly out of sync.
+    fn sample(
+        &self,
+        position: Vec3,
+        time: f32,
+    ) -> Vec3 {
+        if self.amplitude == 0. {
+            return Vec3::ZERO;
+        }
+        let t = time * self.frequency + position.dot(Vec3::new(0.31, 0.17, 0.23));
+        let seed = self.seed.wrapping_mul(3);
+        Vec3::new(
+            value_noise(seed, t),
+            value_noise(seed.wrapping_add(1), t),
+            value_noise(seed.wrapping_add(2), t),
+        ) * self.amplitude
+    }
+}
+
+/// Returns the smooth noise in `[-1, 1]` interpolating random values at integer `t`.
+fn value_noise(
+    seed: u32,
+    t: f32,
+) -> f32 {
+    let i = t.floor();
+    let f = t - i;
+    let s = f * f * (3. - 2. * f);
+    let i = i as i32;
+    let a = hash(seed, i);
+    let b = hash(seed, i.wrapping_add(1));
+    a + (b - a) * s
+}
+
+/// Returns the random value in `[-1, 1]` for `x`.
+fn hash(
+    seed: u32,
+    x: i32,
+) -> f32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x9E37_79B9);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32 * 2. - 1.
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::spring_bone::wind::{
+        Turbulence, WindField, WindFieldShape, WindZone, WindZoneMode,
+    };
+    use crate::vrm::spring_bone::world_collider::SpringColliderTargets;
+    use crate::vrm::spring_bone::SpringRoot;
+    use bevy::math::Vec3;
+    use bevy::prelude::{Entity, GlobalTransform, Transform};
+    use bevy::utils::default;
+
+    fn turbulent(seed: u32) -> WindField {
+        WindField {
+            shape: WindFieldShape::Directional { direction: Vec3::X },
+            strength: 2.,
+            turbulence: Turbulence {
+                amplitude: 0.5,
+                frequency: 3.,
+                seed,
+            },
+        }
+    }
+
+    #[test]
+    fn directional_wind() {
+        let wind = WindField {
+            shape: WindFieldShape::Directional {
+                direction: Vec3::new(2., 0., 0.),
+            },
+            strength: 3.,
+            turbulence: Turbulence::default(),
+        };
+        assert_eq!(wind.force_at(Vec3::new(5., 1., 0.), 10.), Vec3::X * 3.);
+    }
+
+    #[test]
+    fn spherical_wind_falls_off() {
+        let wind = WindField {
+            shape: WindFieldShape::Spherical {
+                center: Vec3::ZERO,
+                radius: 2.,
+            },
+            strength: 4.,
+            turbulence: Turbulence::default(),
+        };
+        assert_eq!(wind.force_at(Vec3::Y, 0.), Vec3::Y * 2.);
+        assert_eq!(wind.force_at(Vec3::Y * 3., 0.), Vec3::ZERO);
+    }
+
+    #[test]
+    fn zone_blows_forward() {
+        let zone = WindZone {
+            mode: WindZoneMode::Directional,
+            ..Default::default()
+        };
+        let gtf = GlobalTransform::from(Transform::default().looking_to(Vec3::X, Vec3::Y));
+        let force = WindField::from_zone(&zone, &gtf).force_at(Vec3::ZERO, 0.);
+        assert!(force.abs_diff_eq(Vec3::X, 1e-5));
+    }
+
+    #[test]
+    fn zone_blows_only_on_targets() {
+        let vrm = Entity::from_raw(1);
+        let zone = WindZone {
+            targets: SpringColliderTargets::Vrms(vec![vrm]),
+            ..default()
+        };
+        assert!(zone.affects(&SpringRoot {
+            vrm: Some(vrm),
+            ..default()
+        }));
+        assert!(!zone.affects(&SpringRoot {
+            vrm: Some(Entity::from_raw(2)),
+            ..default()
+        }));
+        assert!(!zone.affects(&SpringRoot::default()));
+    }
+
+    #[test]
+    fn turbulence_is_deterministic() {
+        let position = Vec3::new(0.1, 1.2, -0.3);
+        for time in [0., 0.37, 1.5, 42.] {
+            assert_eq!(
+                turbulent(7).force_at(position, time),
+                turbulent(7).force_at(position, time)
+            );
+        }
+        assert_ne!(
+            turbulent(7).force_at(position, 0.37),
+            turbulent(8).force_at(position, 0.37)
+        );
+    }
+
+    #[test]
+    fn turbulence_is_bounded() {
+        for i in 0..100 {
+            let force = turbulent(1).force_at(Vec3::ZERO, i as f32 * 0.13);
+            assert!((force - Vec3::X * 2.).abs().max_element() <= 1. + 1e-5);
+        }
+    }
+}
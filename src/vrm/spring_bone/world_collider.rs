@@ -0,0 +1,268 @@
+//! Colliders that affect spring chains regardless of their collider groups.
+//!
+//! [`SpringCollider`] is a standalone collider in the world such as a desk,
+//! and [`ShareSpringColliders`] lets the colliders of a VRM affect the spring chains of other VRMs.
+
+use crate::vrm::extensions::vrmc_spring_bone::SpringColliderShape;
+use crate::vrm::spring_bone::attach::AttachedColliderShapes;
+use crate::vrm::spring_bone::solver::SpringSolverCollider;
+use crate::vrm::spring_bone::switch::DisabledSpringCollider;
+use crate::vrm::spring_bone::update::update_spring_bones;
+use crate::vrm::spring_bone::SpringRoot;
+use bevy::app::{App, PostUpdate};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SpringWorldColliderPlugin;
+
+impl Plugin for SpringWorldColliderPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<SpringCollider>()
+            .register_type::<SpringColliderTargets>()
+            .register_type::<ShareSpringColliders>()
+            .register_type::<SharedSpringColliders>()
+            .add_systems(
+                PostUpdate,
+                cache_shared_colliders.before(update_spring_bones),
+            );
+    }
+}
+
+/// A standalone collider that is not part of any VRM.
+///
+/// The shape follows the [`GlobalTransform`] of the entity.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+#[require(Transform)]
+pub struct SpringCollider {
+    pub shape: SpringColliderShape,
+    pub targets: SpringColliderTargets,
+
+    /// If not empty, only the springs of the listed names are affected.
+    pub springs: Vec<String>,
+}
+
+/// The VRMs whose spring chains are affected by a collider or a [`WindZone`](crate::vrm::spring_bone::wind::WindZone).
+#[derive(Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Serialize, Deserialize, Default)]
+pub enum SpringColliderTargets {
+    #[default]
+    All,
+
+    /// Only the VRMs of the listed entities.
+    Vrms(Vec<Entity>),
+}
+
+impl SpringColliderTargets {
+    /// Returns `true` if the spring chains of `vrm` are affected.
+    ///
+    /// The chains that do not belong to any VRM are only affected by [`SpringColliderTargets::All`].
+    pub fn contains(
+        &self,
+        vrm: Option<Entity>,
+    ) -> bool {
+        match self {
+            Self::All => true,
+            Self::Vrms(vrms) => vrm.is_some_and(|vrm| vrms.contains(&vrm)),
+        }
+    }
+}
+
+/// If this component is attached to the VRM entity,
+/// the colliders of the VRM also affect the spring chains of other VRMs.
+///
+/// The colliders never affect the VRM itself more than its collider groups define.
+#[derive(Component, Reflect, Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[reflect(Component, Serialize, Deserialize, Default)]
+#[require(SharedSpringColliders)]
+pub struct ShareSpringColliders {
+    pub targets: SpringColliderTargets,
+}
+
+/// The collider entities of a VRM with [`ShareSpringColliders`].
+///
+/// The entities are cached when the sharing starts or the colliders of the VRM are attached,
+/// so the hierarchy of the VRM is not walked every frame.
+#[derive(Component, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component, Default)]
+pub struct SharedSpringColliders(Vec<Entity>);
+
+pub(crate) fn cache_shared_colliders(
+    mut vrms: Query<
+        (Entity, &mut SharedSpringColliders),
+        Or<(Changed<ShareSpringColliders>, Added<AttachedColliderShapes>)>,
+    >,
+    children: Query<&Children>,
+    colliders: Query<(), With<SpringColliderShape>>,
+) {
+    for (vrm, mut shared) in vrms.iter_mut() {
+        shared.0 = children
+            .iter_descendants(vrm)
+            .filter(|entity| colliders.contains(*entity))
+            .collect();
+    }
+}
+
+/// A collider outside of the collider groups of the chain, gathered for a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ExternalCollider {
+    pub collider: SpringSolverCollider,
+
+    /// The VRM that owns the collider, if shared by [`ShareSpringColliders`].
+    pub owner: Option<Entity>,
+
+    pub targets: SpringColliderTargets,
+
+    /// If not empty, only the springs of the listed names are affected.
+    pub springs: Vec<String>,
+}
+
+impl ExternalCollider {
+    /// Returns `true` if the collider affects the spring chain.
+    pub fn affects(
+        &self,
+        spring_root: &SpringRoot,
+    ) -> bool {
+        let vrm = spring_root.vrm;
+        (self.owner.is_none() || self.owner != vrm)
+            && self.targets.contains(vrm)
+            && (self.springs.is_empty() || self.springs.contains(&spring_root.name))
+    }
+}
+
+/// Collects the standalone colliders and the shared colliders of VRMs into `external`.
+///
+/// `global_transform` returns the global transform of the entity.
+pub(crate) fn collect_external_colliders(
+    external: &mut Vec<ExternalCollider>,
+    world_colliders: &Query<(Entity, &SpringCollider)>,
+    sharing_vrms: &Query<(Entity, &ShareSpringColliders, &SharedSpringColliders)>,
+    colliders: &Query<&SpringColliderShape, Without<DisabledSpringCollider>>,
+    global_transform: impl Fn(Entity) -> Option<GlobalTransform>,
+) {
+    external.clear();
+    external.extend(world_colliders.iter().filter_map(|(entity, collider)| {
+        Some(ExternalCollider {
+            collider: SpringSolverCollider {
+                shape: collider.shape,
+                transform: global_transform(entity)?,
+            },
+            owner: None,
+            targets: collider.targets.clone(),
+            springs: collider.springs.clone(),
+        })
+    }));
+    for (vrm, share, shared) in sharing_vrms.iter() {
+        external.extend(shared.0.iter().filter_map(|&entity| {
+            Some(ExternalCollider {
+                collider: SpringSolverCollider {
+                    shape: *colliders.get(entity).ok()?,
+                    transform: global_transform(entity)?,
+                },
+                owner: Some(vrm),
+                targets: share.targets.clone(),
+                springs: Vec::new(),
+            })
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::test_app;
+    use crate::vrm::extensions::vrmc_spring_bone::SpringColliderShape;
+    use crate::vrm::spring_bone::attach::AttachedColliderShapes;
+    use crate::vrm::spring_bone::solver::SpringSolverCollider;
+    use crate::vrm::spring_bone::world_collider::{
+        cache_shared_colliders, ExternalCollider, ShareSpringColliders, SharedSpringColliders,
+        SpringColliderTargets,
+    };
+    use crate::vrm::spring_bone::SpringRoot;
+    use bevy::app::Update;
+    use bevy::prelude::{BuildChildren, Entity};
+    use bevy::utils::default;
+
+    fn chain_of(vrm: Option<Entity>) -> SpringRoot {
+        SpringRoot {
+            name: "Hair".to_string(),
+            vrm,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn shared_collider_does_not_affect_owner() {
+        let owner = Entity::from_raw(1);
+        let other = Entity::from_raw(2);
+        let collider = ExternalCollider {
+            collider: SpringSolverCollider::default(),
+            owner: Some(owner),
+            targets: SpringColliderTargets::All,
+            springs: Vec::new(),
+        };
+        assert!(!collider.affects(&chain_of(Some(owner))));
+        assert!(collider.affects(&chain_of(Some(other))));
+        assert!(collider.affects(&chain_of(None)));
+    }
+
+    #[test]
+    fn filter_targets() {
+        let target = Entity::from_raw(1);
+        let collider = ExternalCollider {
+            collider: SpringSolverCollider::default(),
+            owner: None,
+            targets: SpringColliderTargets::Vrms(vec![target]),
+            springs: Vec::new(),
+        };
+        assert!(collider.affects(&chain_of(Some(target))));
+        assert!(!collider.affects(&chain_of(Some(Entity::from_raw(2)))));
+        assert!(!collider.affects(&chain_of(None)));
+    }
+
+    #[test]
+    fn filter_springs() {
+        let collider = ExternalCollider {
+            collider: SpringSolverCollider::default(),
+            owner: None,
+            targets: SpringColliderTargets::All,
+            springs: vec!["Skirt".to_string()],
+        };
+        assert!(!collider.affects(&chain_of(None)));
+        assert!(collider.affects(&SpringRoot {
+            name: "Skirt".to_string(),
+            ..default()
+        }));
+    }
+
+    #[test]
+    fn cache_colliders_attached_after_sharing() {
+        let mut app = test_app();
+        app.add_systems(Update, cache_shared_colliders);
+        let vrm = app.world_mut().spawn(ShareSpringColliders::default()).id();
+        app.update();
+        assert!(app
+            .world()
+            .get::<SharedSpringColliders>(vrm)
+            .unwrap()
+            .0
+            .is_empty());
+
+        let collider = app
+            .world_mut()
+            .spawn(SpringColliderShape::default())
+            .set_parent(vrm)
+            .id();
+        app.world_mut().spawn_empty().set_parent(vrm);
+        app.world_mut()
+            .entity_mut(vrm)
+            .insert(AttachedColliderShapes);
+        app.update();
+        assert_eq!(
+            app.world().get::<SharedSpringColliders>(vrm).unwrap().0,
+            vec![collider]
+        );
+    }
+}
@@ -6,7 +6,12 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VrmaExpressions {
+    #[serde(default)]
     pub preset: HashMap<String, VrmNode>,
+
+    /// The custom expressions, which are retargeted to the custom expressions of the VRM with the same names.
+    #[serde(default)]
+    pub custom: HashMap<String, VrmNode>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
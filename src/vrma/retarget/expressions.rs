@@ -1,6 +1,6 @@
 use crate::system_param::child_searcher::ChildSearcher;
 
-use crate::vrm::expressions::VrmExpressionRegistry;
+use crate::vrm::expressions::{ExpressionKey, VrmExpressionRegistry, VrmExpressionWeights};
 use crate::vrma::retarget::{CurrentRetargeting, RetargetBindingSystemSet};
 use crate::vrma::spawn::VrmaExpressionNames;
 use crate::vrma::{RetargetSource, RetargetTo};
@@ -8,8 +8,8 @@ use bevy::app::{App, Update};
 use bevy::hierarchy::Children;
 use bevy::log::debug;
 use bevy::prelude::{
-    Added, Changed, Commands, Component, Entity, IntoSystemConfigs, MorphWeights, Plugin, Query,
-    Reflect, Transform, With,
+    Added, Changed, Commands, Component, Entity, IntoSystemConfigs, Plugin, Query, Reflect,
+    Transform, With,
 };
 
 pub struct VrmaRetargetExpressionsPlugin;
@@ -19,26 +19,22 @@ impl Plugin for VrmaRetargetExpressionsPlugin {
         &self,
         app: &mut App,
     ) {
-        app.register_type::<RetargetExpressionTo>()
-            .register_type::<BindExpressionNode>()
-            .add_systems(
-                Update,
-                (
-                    retarget_expressions_to_mascot,
-                    bind_expressions.in_set(RetargetBindingSystemSet),
-                ),
-            );
+        app.register_type::<RetargetExpressionTo>().add_systems(
+            Update,
+            (
+                retarget_expressions_to_mascot,
+                bind_expressions.in_set(RetargetBindingSystemSet),
+            ),
+        );
     }
 }
 
-#[derive(Reflect)]
-struct BindExpressionNode {
-    pub expression_entity: Entity,
-    pub index: usize,
-}
-
+/// The expression of the VRM that the VRMA expression entity drives.
 #[derive(Component, Reflect)]
-struct RetargetExpressionTo(Vec<BindExpressionNode>);
+struct RetargetExpressionTo {
+    vrm: Entity,
+    expression: ExpressionKey,
+}
 
 fn retarget_expressions_to_mascot(
     mut commands: Commands,
@@ -50,51 +46,100 @@ fn retarget_expressions_to_mascot(
         let Ok(vrm_expressions) = mascots.get(retarget.0) else {
             continue;
         };
-        for expression_name in expressions.iter() {
-            let Some(vrma_expression_entity) =
-                searcher.find_from_name(vrma_entity, expression_name)
+        for (expression, node_name) in expressions.iter() {
+            let Some(vrma_expression_entity) = searcher.find_from_name(vrma_entity, node_name)
             else {
-                debug!("[Expressions] expression entity not found: {expression_name}");
+                debug!("[Expressions] expression entity not found: {expression}");
                 continue;
             };
-            let Some(nodes) = vrm_expressions.get(expression_name) else {
-                debug!("[Expressions] expression nodes not found: {expression_name}");
+            if !vrm_expressions.contains_key(expression) {
+                debug!("[Expressions] expression nodes not found: {expression}");
                 continue;
-            };
-            let binds = nodes
-                .iter()
-                .filter_map(|node| {
-                    Some(BindExpressionNode {
-                        expression_entity: searcher.find_from_name(retarget.0, &node.name)?,
-                        index: node.morph_target_index,
-                    })
-                })
-                .collect::<Vec<_>>();
-            commands
-                .entity(vrma_expression_entity)
-                .insert((RetargetSource, RetargetExpressionTo(binds)));
+            }
+            commands.entity(vrma_expression_entity).insert((
+                RetargetSource,
+                RetargetExpressionTo {
+                    vrm: retarget.0,
+                    expression: expression.clone(),
+                },
+            ));
         }
     }
 }
 
 fn bind_expressions(
-    mut expressions: Query<&mut MorphWeights>,
+    mut vrms: Query<&mut VrmExpressionWeights>,
     vrma: Query<
         (&Transform, &RetargetExpressionTo),
         (Changed<Transform>, With<CurrentRetargeting>),
     >,
 ) {
-    for (tf, RetargetExpressionTo(binds)) in vrma.iter() {
-        // VRMA uses x coordinate to represent expression weight.
-        let weight = tf.translation.x;
-        for BindExpressionNode {
-            expression_entity,
-            index,
-        } in binds.iter()
-        {
-            if let Ok(mut morph_weights) = expressions.get_mut(*expression_entity) {
-                morph_weights.weights_mut()[*index] = weight;
-            }
+    for (tf, RetargetExpressionTo { vrm, expression }) in vrma.iter() {
+        if let Ok(mut weights) = vrms.get_mut(*vrm) {
+            // VRMA uses x coordinate to represent expression weight.
+            weights.set_weight(expression, tf.translation.x);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::expressions::{ExpressionKey, VrmExpressionRegistry, VrmExpressionWeights};
+    use crate::vrm::extensions::VrmExtensions;
+    use crate::vrma::retarget::expressions::{bind_expressions, retarget_expressions_to_mascot};
+    use crate::vrma::retarget::CurrentRetargeting;
+    use crate::vrma::spawn::VrmaExpressionNames;
+    use crate::vrma::RetargetTo;
+    use bevy::asset::Assets;
+    use bevy::core::Name;
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::hierarchy::BuildChildren;
+    use bevy::prelude::Transform;
+
+    #[test]
+    fn retarget_custom_expression_named_after_preset() -> TestResult {
+        let extensions: VrmExtensions = serde_json::from_str(
+            r#"{
+                "VRMC_vrm": {
+                    "specVersion": "1.0",
+                    "humanoid": { "humanBones": {} },
+                    "expressions": {
+                        "preset": { "happy": {} },
+                        "custom": { "happy": {} }
+                    }
+                }
+            }"#,
+        )?;
+        let registry = VrmExpressionRegistry::new(&extensions, &Assets::default(), &[], &[]);
+        let mut app = test_app();
+        let vrm = app
+            .world_mut()
+            .spawn((registry, VrmExpressionWeights::default()))
+            .id();
+        let node = app
+            .world_mut()
+            .spawn((Name::new("customHappy"), Transform::default()))
+            .id();
+        app.world_mut()
+            .spawn((
+                RetargetTo(vrm),
+                VrmaExpressionNames(vec![(
+                    ExpressionKey::custom("happy"),
+                    Name::new("customHappy"),
+                )]),
+            ))
+            .add_child(node);
+        app.world_mut()
+            .run_system_once(retarget_expressions_to_mascot)?;
+        app.world_mut()
+            .entity_mut(node)
+            .insert((CurrentRetargeting, Transform::from_xyz(0.7, 0., 0.)));
+        app.world_mut().run_system_once(bind_expressions)?;
+
+        let weights = app.world().get::<VrmExpressionWeights>(vrm).unwrap();
+        assert!((weights.get_custom(&"happy".into()) - 0.7).abs() < f32::EPSILON);
+        assert!(weights.get(&"happy".into()).abs() < f32::EPSILON);
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
+use crate::vrm::expressions::ExpressionKey;
+use crate::vrm::extensions::VrmNode;
 use crate::vrm::humanoid_bone::{HumanoidBoneRegistry, HumanoidBonesAttached};
-use crate::vrm::VrmExpression;
 use crate::vrma::animation::VrmAnimationGraph;
 use crate::vrma::extensions::VrmaExtensions;
 use crate::vrma::loader::VrmaAsset;
@@ -28,19 +29,35 @@ impl Plugin for VrmaSpawnPlugin {
     }
 }
 
+/// The node names of the expressions in `VRMC_vrm_animation`, keyed by the expression they drive.
 #[derive(Component, Deref, Reflect)]
-pub struct VrmaExpressionNames(Vec<VrmExpression>);
+pub struct VrmaExpressionNames(pub(crate) Vec<(ExpressionKey, Name)>);
 
 impl VrmaExpressionNames {
-    pub fn new(extensions: &VrmaExtensions) -> Self {
+    pub fn new(
+        extensions: &VrmaExtensions,
+        node_assets: &Assets<GltfNode>,
+        nodes: &[Handle<GltfNode>],
+    ) -> Self {
         let Some(expressions) = extensions.vrmc_vrm_animation.expressions.as_ref() else {
             return Self(Vec::default());
         };
+        let node_name = |target: &VrmNode| {
+            let node = node_assets.get(nodes.get(target.node)?)?;
+            Some(Name::new(node.name.clone()))
+        };
         Self(
             expressions
                 .preset
-                .keys()
-                .map(|expression| VrmExpression(expression.clone()))
+                .iter()
+                .map(|(name, target)| (ExpressionKey::preset(name.as_str()), target))
+                .chain(
+                    expressions
+                        .custom
+                        .iter()
+                        .map(|(name, target)| (ExpressionKey::custom(name.as_str()), target)),
+                )
+                .filter_map(|(key, target)| Some((key, node_name(target)?)))
                 .collect(),
         )
     }
@@ -95,7 +112,7 @@ fn spawn_vrma(
             VrmaDuration(obtain_vrma_duration(&clip_assets, &vrma.gltf.animations)),
             VrmaPath(vrma_path),
             VrmAnimationGraph::new(vrma.gltf.animations.to_vec(), &mut animation_graphs),
-            VrmaExpressionNames::new(&extensions),
+            VrmaExpressionNames::new(&extensions, &node_assets, &vrma.gltf.nodes),
             HumanoidBoneRegistry::new(
                 &extensions.vrmc_vrm_animation.humanoid.human_bones,
                 &node_assets,